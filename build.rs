@@ -0,0 +1,50 @@
+//! Checks that every scaffolded day has an input file when the `embed_inputs` feature is enabled.
+//! Without this check, a missing input would surface as an `include_str!` error inside the `solution!` macro.
+use std::{env, fs, path::Path, process};
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+
+    if env::var_os("CARGO_FEATURE_EMBED_INPUTS").is_none() {
+        return;
+    }
+
+    println!("cargo:rerun-if-changed=src/bin");
+    println!("cargo:rerun-if-changed=data/inputs");
+
+    let Ok(entries) = fs::read_dir("src/bin") else {
+        return;
+    };
+
+    let mut days: Vec<String> = entries
+        .filter_map(Result::ok)
+        .filter_map(|entry| {
+            let path = entry.path();
+            let is_solution = path.extension().is_some_and(|ext| ext == "rs");
+            let day = path.file_stem()?.to_str()?.to_string();
+            (is_solution && day.parse::<u8>().is_ok()).then_some(day)
+        })
+        .collect();
+    days.sort();
+
+    let mut missing = vec![];
+
+    for day in days {
+        let input_path = format!("data/inputs/{day}.txt");
+        match fs::metadata(Path::new(&input_path)) {
+            Ok(metadata) if metadata.len() == 0 => {
+                println!("cargo:warning=embed_inputs: input file \"{input_path}\" is empty.");
+            }
+            Ok(_) => {}
+            Err(_) => missing.push((day, input_path)),
+        }
+    }
+
+    if !missing.is_empty() {
+        eprintln!("error: the `embed_inputs` feature requires an input file for every solution.");
+        for (day, input_path) in missing {
+            eprintln!("  missing \"{input_path}\", run \"cargo download {day}\" to fetch it.");
+        }
+        process::exit(1);
+    }
+}
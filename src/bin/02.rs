@@ -1,123 +1,230 @@
-use std::collections::HashMap;
+use std::sync::{Once, OnceLock};
+
+use advent_of_code::parse::{lines, report, ParseError};
+use advent_of_code::scan::ScanError;
+use advent_of_code::template::{arg_value, has_flag, input::load};
 
 advent_of_code::solution!(2);
-// Define a type alias for a HashMap where the key is a String (representing a tile colour) and the value is a u32 (representing the number of colour drawn from a bag).
-type ColourCounts = HashMap<String, u32>;
 
-pub fn part_one(input: &str) -> Option<u32> {
-    // Parse the input string into a games map.
-    let games_map = parse_games(input);
+/// The bag of part one. `--bag "<cubes>"` or `data/config/02.txt` replace it.
+const DEFAULT_BAG: &str = "12 red, 13 green, 14 blue";
+
+/// Explanations are only printed for the first run of part one, not for every benchmark sample.
+static EXPLAIN: Once = Once::new();
+
+/// The cubes of the bag, read once so that benchmark samples do not read the arguments or the
+/// config file again. [`None`] if the bag is invalid, which has been reported.
+static BAG: OnceLock<Option<Vec<(String, u32)>>> = OnceLock::new();
+
+/// A colour, as an index into [`Colours`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Colour(usize);
+
+/// The names of all colours, each stored once.
+#[derive(Debug, Clone, Default)]
+struct Colours {
+    names: Vec<String>,
+}
+
+impl Colours {
+    fn intern(&mut self, name: &str) -> Colour {
+        match self.get(name) {
+            Some(colour) => colour,
+            None => {
+                self.names.push(name.to_string());
+                Colour(self.names.len() - 1)
+            }
+        }
+    }
+
+    fn get(&self, name: &str) -> Option<Colour> {
+        self.names.iter().position(|n| n == name).map(Colour)
+    }
+
+    fn name(&self, colour: Colour) -> &str {
+        &self.names[colour.0]
+    }
 
-    // Define a HashMap with maximum counts for each colour (representing the restriction for possible games).
-    let max_counts = HashMap::from([
-        ("red".to_string(), 12),
-        ("blue".to_string(), 14),
-        ("green".to_string(), 13),
-    ]);
+    fn len(&self) -> usize {
+        self.names.len()
+    }
+}
+
+/// How many cubes of each colour were shown at once. Colours are ids into [`Games::colours`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Draw(Vec<(Colour, u32)>);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Game {
+    id: u32,
+    draws: Vec<Draw>,
+}
 
-    // Filter the games based on the maximum counts defined above.
-    let filtered_games = filter_games_by_max_counts(&games_map, &max_counts);
+/// All games of the input, together with every colour that occurs in them.
+#[derive(Debug, Clone, Default)]
+struct Games {
+    colours: Colours,
+    games: Vec<Game>,
+}
 
-    // Calculate the sum of the filtered game numbers using iterator methods.
-    let sum: u32 = filtered_games.iter().sum();
+/// The most cubes of each colour the bag holds, indexed by colour id.
+/// Colours the bag does not mention are not limited.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Bag {
+    limits: Vec<Option<u32>>,
+}
 
-    // Return the sum wrapped in Some, a Rust enum for handling optional values.
-    Some(sum)
+/// Why a game is impossible: one of its draws showed more cubes of a colour than the bag holds.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Violation {
+    /// The index of the draw, counting from zero.
+    draw: usize,
+    colour: Colour,
+    count: u32,
+    limit: u32,
+}
+
+pub fn part_one(input: &str) -> Option<u32> {
+    let mut games = report(parse_games(input))?;
+    let cubes = BAG.get_or_init(load_bag).as_ref()?;
+    let bag = Bag::new(cubes, &mut games.colours);
+
+    if has_flag("--explain") {
+        EXPLAIN.call_once(|| explain(&games, &bag));
+    }
+
+    Some(
+        games
+            .games
+            .iter()
+            .filter(|game| bag.violation(game).is_none())
+            .map(|game| game.id)
+            .sum(),
+    )
 }
 
 pub fn part_two(input: &str) -> Option<u32> {
-    let games_map = parse_games(input);
-    return Some(sum_power(&games_map));
-}
-
-// Function to parse the input string into a HashMap.
-fn parse_games(input: &str) -> HashMap<u32, Vec<ColourCounts>> {
-    input
-        .lines()
-        .filter_map(|line| {
-            // Rust's pattern matching
-            let mut parts = line.split(':');
-            let game_number = parts
-                .next()?
-                .trim()
-                .split_whitespace()
-                .nth(1)?
-                .parse()
-                .ok()?;
-            let subsets = parts
-                .next()?
-                .trim()
-                .split(';')
-                .map(|subset| {
-                    subset
-                        .split(',')
-                        .filter_map(|colour_count| {
-                            let mut parts = colour_count.trim().split_whitespace();
-                            let count = parts.next()?.parse().ok()?;
-                            let colour = parts.next()?.to_string();
-                            Some((colour, count))
-                        })
-                        .collect()
-                })
-                .collect();
-            Some((game_number, subsets))
-        })
-        .collect()
+    let games = report(parse_games(input))?;
+    Some(
+        games
+            .games
+            .iter()
+            .map(|game| power(game, games.colours.len()))
+            .sum(),
+    )
 }
 
-fn filter_games_by_max_counts(
-    games_map: &HashMap<u32, Vec<ColourCounts>>,
-    max_counts: &HashMap<String, u32>,
-) -> Vec<u32> {
-    games_map
-        .iter()
-        .filter(|(_, subsets)| {
-            subsets.iter().all(|subset| {
-                subset.iter().all(|(colour, &count)| {
-                    max_counts
-                        .get(colour)
-                        .map_or(true, |&max_count| count <= max_count)
-                })
+fn parse_games(input: &str) -> Result<Games, ParseError> {
+    let mut games = Games::default();
+
+    for line in lines(input).filter(|line| !line.text.trim().is_empty()) {
+        let (id, draws) = advent_of_code::scan!("Game {}: {}", line.text => u32, str)
+            .map_err(|e| line.error(e))?;
+        let draws = draws
+            .split(';')
+            .map(|draw| {
+                let cubes = parse_cubes(draw)?;
+                Ok(Draw(
+                    cubes
+                        .into_iter()
+                        .map(|(colour, count)| (games.colours.intern(colour), count))
+                        .collect(),
+                ))
             })
+            .collect::<Result<_, ScanError>>()
+            .map_err(|e| line.error(e))?;
+        games.games.push(Game { id, draws });
+    }
+
+    Ok(games)
+}
+
+/// Parses a comma-separated list of cubes like `3 blue, 4 red`.
+fn parse_cubes(text: &str) -> Result<Vec<(&str, u32)>, ScanError> {
+    text.split(',')
+        .map(|cubes| {
+            let (count, colour) = advent_of_code::scan!("{} {}", cubes.trim() => u32, str)?;
+            Ok((colour, count))
         })
-        .map(|(&game, _)| game)
         .collect()
 }
 
-// Function to calculate the sum of powers of the games.
-fn sum_power(games_map: &HashMap<u32, Vec<ColourCounts>>) -> u32 {
-    let mut min_colours_per_game = HashMap::new();
+/// Reads the bag from `--bag`, then `data/config/02.txt`, falling back to [`DEFAULT_BAG`].
+fn load_bag() -> Option<Vec<(String, u32)>> {
+    let text = arg_value("--bag")
+        .or_else(|| load("config", DAY, false).ok().map(|config| config.text))
+        .unwrap_or_else(|| DEFAULT_BAG.to_string());
 
-    for (&game_number, subsets) in games_map {
-        let mut max_red = 0;
-        let mut max_blue = 0;
-        let mut max_green = 0;
+    parse_bag(text.trim())
+        .map_err(|e| eprintln!("Error: invalid bag: {e}"))
+        .ok()
+}
 
-        for subset in subsets {
-            for (colour, &count) in subset {
-                match colour.as_str() {
-                    "red" => max_red = max_red.max(count),
-                    "blue" => max_blue = max_blue.max(count),
-                    "green" => max_green = max_green.max(count),
-                    _ => {}
-                }
-            }
+fn parse_bag(text: &str) -> Result<Vec<(String, u32)>, ScanError> {
+    Ok(parse_cubes(text)?
+        .into_iter()
+        .map(|(colour, count)| (colour.to_string(), count))
+        .collect())
+}
+
+impl Bag {
+    fn new(cubes: &[(String, u32)], colours: &mut Colours) -> Self {
+        let cubes: Vec<(Colour, u32)> = cubes
+            .iter()
+            .map(|(colour, count)| (colours.intern(colour), *count))
+            .collect();
+        let mut limits = vec![None; colours.len()];
+        for (colour, count) in cubes {
+            limits[colour.0] = Some(count);
         }
+        Bag { limits }
+    }
 
-        min_colours_per_game.insert(
-            game_number,
-            HashMap::from([
-                ("red".to_string(), max_red),
-                ("blue".to_string(), max_blue),
-                ("green".to_string(), max_green),
-            ]),
-        );
+    /// Returns the first draw of `game` that needs more cubes than the bag holds.
+    fn violation(&self, game: &Game) -> Option<Violation> {
+        game.draws
+            .iter()
+            .enumerate()
+            .find_map(|(draw, Draw(cubes))| {
+                cubes.iter().find_map(|&(colour, count)| {
+                    let limit = self.limits.get(colour.0).copied().flatten()?;
+                    (count > limit).then_some(Violation {
+                        draw,
+                        colour,
+                        count,
+                        limit,
+                    })
+                })
+            })
     }
+}
+
+/// The product of the fewest cubes of each colour that make `game` possible.
+/// Colours that `game` never shows count as zero cubes.
+fn power(game: &Game, colour_count: usize) -> u32 {
+    let mut fewest = vec![0; colour_count];
+    for Draw(cubes) in &game.draws {
+        for &(colour, count) in cubes {
+            fewest[colour.0] = fewest[colour.0].max(count);
+        }
+    }
+    fewest.iter().product()
+}
 
-    min_colours_per_game
-        .values()
-        .map(|colours| colours.values().product::<u32>())
-        .sum()
+/// Prints which draw makes each impossible game impossible.
+fn explain(games: &Games, bag: &Bag) {
+    for game in &games.games {
+        if let Some(v) = bag.violation(game) {
+            println!(
+                "Game {} is impossible: draw {} shows {} {}, but the bag only holds {}.",
+                game.id,
+                v.draw + 1,
+                v.count,
+                games.colours.name(v.colour),
+                v.limit
+            );
+        }
+    }
 }
 
 #[cfg(test)]
@@ -127,12 +234,60 @@ mod tests {
     #[test]
     fn test_part_one() {
         let result = part_one(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, None);
+        assert_eq!(result, Some(8));
     }
 
     #[test]
     fn test_part_two() {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, None);
+        assert_eq!(result, Some(2286));
+    }
+
+    #[test]
+    fn test_violations() {
+        let mut games = parse_games(&advent_of_code::template::read_file("examples", DAY)).unwrap();
+        let bag = Bag::new(&parse_bag(DEFAULT_BAG).unwrap(), &mut games.colours);
+        let violations: Vec<(u32, Violation)> = games
+            .games
+            .iter()
+            .filter_map(|game| Some((game.id, bag.violation(game)?)))
+            .collect();
+
+        let red = games.colours.get("red").unwrap();
+        let blue = games.colours.get("blue").unwrap();
+        assert_eq!(
+            violations,
+            [
+                (
+                    3,
+                    Violation {
+                        draw: 0,
+                        colour: red,
+                        count: 20,
+                        limit: 12
+                    }
+                ),
+                (
+                    4,
+                    Violation {
+                        draw: 2,
+                        colour: blue,
+                        count: 15,
+                        limit: 14
+                    }
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_any_number_of_colours() {
+        let input = "Game 1: 2 red, 1 teal\nGame 2: 3 teal; 1 red\n";
+        let mut games = parse_games(input).unwrap();
+        let bag = Bag::new(&parse_bag("2 teal").unwrap(), &mut games.colours);
+        assert!(bag.violation(&games.games[0]).is_none());
+        assert_eq!(bag.violation(&games.games[1]).map(|v| v.count), Some(3));
+        assert_eq!(part_two(input), Some(2 + 3));
+        assert_eq!(part_two("Game 1: 2 red, blue\n"), None);
     }
 }
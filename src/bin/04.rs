@@ -1,6 +1,10 @@
-use colored::*; // Import colored crate
+use std::collections::HashSet;
+
+use advent_of_code::grid::Colour;
+use advent_of_code::parse::{lines, report, Line, ParseError};
+use advent_of_code::viz::{self, Frame, Span};
 advent_of_code::solution!(4);
-#[derive(Clone)]
+
 struct GameTicket {
     game_number: usize,
     winning_numbers: Vec<u32>,
@@ -9,159 +13,123 @@ struct GameTicket {
 }
 
 impl GameTicket {
-    fn new(input: &str) -> Self {
-        let parts: Vec<&str> = input.split(": ").collect();
-        if parts.len() != 2 {
-            panic!("Invalid input format");
-        }
-
-        let game_number_str = parts[0]
-            .trim()
-            .split_whitespace()
-            .last()
-            .expect("Invalid game number format");
-        let game_number = game_number_str
-            .parse::<usize>()
-            .expect("Invalid game number");
-
-        let numbers_parts: Vec<&str> = parts[1].split('|').collect();
-        
-        if numbers_parts.len() != 2 {
-            panic!("Invalid input format");
-        }
+    /// Parses a `Card 1: 41 48 83 | 83 86 6` line.
+    fn parse(line: Line) -> Result<Self, ParseError> {
+        let (label, numbers) = line.split_label()?;
+        let [game_number] = label.unsigned_array()?;
 
-        let winning_numbers: Vec<u32> = numbers_parts[0]
-            .trim()
-            .split_whitespace()
-            .map(|num| num.parse::<u32>().expect("Invalid number"))
-            .collect();
+        let (winning_part, ticket_part) = numbers.text.split_once('|').ok_or_else(|| {
+            line.error("expected \"|\" between the winning and the ticket numbers")
+        })?;
 
-        let ticket_numbers: Vec<u32> = numbers_parts[1]
-            .trim()
-            .split_whitespace()
-            .map(|num| num.parse::<u32>().expect("Invalid number"))
-            .collect();
+        let winning_numbers: Vec<u32> = numbers.with_text(winning_part).unsigned()?;
+        let ticket_numbers: Vec<u32> = numbers.with_text(ticket_part).unsigned()?;
 
         // Calculate the intersection count for winning ticket numbers
-        let winning_set: std::collections::HashSet<_> = winning_numbers.iter().collect();
+        let winning_set: HashSet<_> = winning_numbers.iter().collect();
         let winning_count = ticket_numbers
             .iter()
             .filter(|num| winning_set.contains(num))
             .count();
 
-        GameTicket {
+        Ok(GameTicket {
             game_number,
             winning_numbers,
             ticket_numbers,
             winning_count,
-        }
+        })
     }
 
-    fn calculate_winnings(&self) -> u32 {
-        if self.winning_count == 0 {
-            return 0; // If there are no winning numbers, return 1
+    /// One point for the first winning number, doubled for each further one.
+    /// Returns [`None`] if the points do not fit into a `u64`, i.e. for more than 64 winning numbers.
+    fn calculate_winnings(&self) -> Option<u64> {
+        match self.winning_count {
+            0 => Some(0),
+            count => 1u64.checked_shl(u32::try_from(count - 1).ok()?),
         }
-
-        let mut winnings = 1; // Start with 2 for the first winning number
-
-        for _ in 1..self.winning_count {
-            winnings *= 2; // Multiply by 2 for each additional winning number
-        }
-
-        winnings
     }
 
-    fn print_color_coded(&self) {
-        let winning_numbers_set: std::collections::HashSet<_> =
-            self.winning_numbers.iter().collect();
-
-        // Printing winning numbers
-        println!(
-            "Winning Numbers: {}",
-            self.winning_numbers
-                .iter()
-                .map(|num| num.to_string())
-                .collect::<Vec<String>>()
-                .join(" ")
-        );
-
-        let ticket_str = self
-            .ticket_numbers
+    /// Lays out the ticket as one line, with the ticket numbers that win highlighted.
+    fn color_coded_line(&self) -> Vec<Span> {
+        let winning_numbers_set: HashSet<_> = self.winning_numbers.iter().collect();
+
+        let winning_str = self
+            .winning_numbers
             .iter()
-            .map(|num| {
-                if winning_numbers_set.contains(num) {
-                    num.to_string().red().to_string()
-                } else {
-                    num.to_string().normal().to_string() 
-                }
-            })
+            .map(|num| format!("{num:>2}"))
             .collect::<Vec<String>>()
-            .join(" "); // Join the strings
+            .join(" ");
+
+        let mut line = vec![Span::plain(format!(
+            "Card {:>3}: {winning_str} |",
+            self.game_number
+        ))];
+
+        for num in &self.ticket_numbers {
+            line.push(Span::plain(" "));
+            if winning_numbers_set.contains(num) {
+                line.push(Span::coloured(format!("{num:>2}"), Colour::Red));
+            } else {
+                line.push(Span::plain(format!("{num:>2}")));
+            }
+        }
 
-        println!("Ticket Numbers: {}", ticket_str);
-        let winnings = self.calculate_winnings();
-        println!("Winnings: {}", winnings);
+        let winnings = self
+            .calculate_winnings()
+            .map_or_else(|| "✖".to_string(), |points| points.to_string());
+        line.push(Span::plain(format!(" => {winnings}")));
+        line
     }
 }
 
-pub fn part_one(input: &str) -> Option<u32> {
-    let game_tickets: Vec<GameTicket> = input.lines().map(|line| GameTicket::new(line)).collect();
-
-    let mut winnings: u32 = 0;
-
-    for ticket in &game_tickets {
-        winnings += ticket.calculate_winnings();
-        ticket.print_color_coded();
-        println!();
-    }
-
-    Some(winnings)
+fn parse_tickets(input: &str) -> Result<Vec<GameTicket>, ParseError> {
+    lines(input)
+        .filter(|line| !line.text.trim().is_empty())
+        .map(GameTicket::parse)
+        .collect()
 }
 
-pub fn part_two(input: &str) -> Option<u32> {
-
-    let mut game_tickets: Vec<GameTicket> = input
-        .lines()
-        .map(|line| GameTicket::new(line))
-        .collect();
+/// Sums the points of all tickets. Returns [`None`] if they do not fit into a `u64`.
+pub fn part_one(input: &str) -> Option<u64> {
+    let game_tickets = report(parse_tickets(input))?;
 
-    let mut instanced_tickets: Vec<GameTicket> = Vec::new();
+    let winnings = game_tickets
+        .iter()
+        .map(GameTicket::calculate_winnings)
+        .try_fold(0u64, |sum, points| sum.checked_add(points?));
 
-    // Instance initial set of tickets
-    for ticket in &game_tickets {
-        let start = ticket.game_number + 1;
-        let end = std::cmp::min(game_tickets.len(), start + ticket.winning_count);
-
-        for idx in start..end {
-            if idx - 1 <= game_tickets.len() {
-                instanced_tickets.push(game_tickets[idx-1].clone());
-            }
-        }
+    if viz::enabled() {
+        viz::record(Frame::from_lines(
+            game_tickets.iter().map(GameTicket::color_coded_line),
+        ));
     }
 
-    // Process newly instanced tickets recursively
-    let mut i = 0;
-    while i < instanced_tickets.len() {
-        let ticket = &instanced_tickets[i];
-        let start = ticket.game_number + 1;
-        let end = std::cmp::min(game_tickets.len(), start + ticket.winning_count);
-
-        for idx in start..end {
-            if idx - 1 <= game_tickets.len() {
-                instanced_tickets.push(game_tickets[idx-1].clone());
-            }
-        }
+    winnings
+}
 
-        i += 1;
+pub fn part_two(input: &str) -> Option<u32> {
+    let game_tickets = report(parse_tickets(input))?;
+
+    // Every card adds its number of copies to the copies of the next `winning_count` cards.
+    // Instead of adding to each of them, `won` carries the copies won for the current card and
+    // `expiring` drops them again after the last card they apply to.
+    let mut expiring = vec![0; game_tickets.len() + 1];
+    let mut won = 0;
+    let mut total = 0;
+
+    for (index, ticket) in game_tickets.iter().enumerate() {
+        won -= expiring[index];
+        let copies = 1 + won;
+        total += copies;
+
+        let end = (index + 1 + ticket.winning_count).min(game_tickets.len());
+        won += copies;
+        expiring[end] += copies;
     }
 
-    // Combine original and instanced tickets
-    game_tickets.extend(instanced_tickets);
-
-    Some(game_tickets.len() as u32)
+    Some(total)
 }
 
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -169,12 +137,44 @@ mod tests {
     #[test]
     fn test_part_one() {
         let result = part_one(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, None);
+        assert_eq!(result, Some(13));
     }
 
     #[test]
     fn test_part_two() {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, None);
+        assert_eq!(result, Some(30));
+    }
+
+    #[test]
+    fn test_last_card_copies() {
+        // The copies of card 1 reach the last card.
+        let input = "Card 1: 1 2 | 1 2\nCard 2: 3 | 4\nCard 3: 5 | 6\n";
+        assert_eq!(part_two(input), Some(1 + 2 + 2));
+    }
+
+    #[test]
+    fn test_invalid_tickets() {
+        let error = parse_tickets("Card 1: 41 48 | 83\nCard 2: 41 48 83\n")
+            .err()
+            .unwrap();
+        assert_eq!(error.line, 2);
+        assert!(parse_tickets("Card x: 1 | 2\n").is_err());
+        assert_eq!(part_one("Card 1: 41 48 | 83\nCard 2\n"), None);
+        // blank lines are not tickets.
+        assert_eq!(part_two("Card 1: 1 | 1\n\nCard 2: 2 | 3\n\n"), Some(3));
+    }
+
+    #[test]
+    fn test_many_winning_numbers() {
+        let card = |count: u32| {
+            let numbers: Vec<String> = (1..=count).map(|n| n.to_string()).collect();
+            let numbers = numbers.join(" ");
+            format!("Card 1: {numbers} | {numbers}\n")
+        };
+        assert_eq!(part_one(&card(33)), Some(1 << 32));
+        assert_eq!(part_one(&card(64)), Some(1 << 63));
+        assert_eq!(part_one(&card(65)), None);
+        assert_eq!(part_one(&(card(64) + &card(64))), None);
     }
 }
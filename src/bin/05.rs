@@ -1,11 +1,22 @@
-use std::collections::HashMap;
-use std::io::Write;
-use std::sync::{Mutex, Arc};
-use rayon::prelude::*;
+use std::ops::Range;
+use std::sync::Once;
+
+use advent_of_code::parse::{report, sections, ParseError, Section};
+use advent_of_code::ranges::{IntervalSet, RangeMap};
+use advent_of_code::template::arg_value;
 advent_of_code::solution!(5);
 
+/// The answer to `--query` is only printed for the first run, not for every benchmark sample.
+static QUERY: Once = Once::new();
+
+/// The seeds to plant and the maps that lead from seeds to locations, in stage order.
+struct Almanac {
+    seeds: Vec<u64>,
+    maps: Vec<LabeledMap>,
+}
+
 struct LabeledMap {
-    map: HashMap<Mapping, Mapping>,
+    map: RangeMap<u64>,
     source_stage: Stage,
     destination_stage: Stage,
 }
@@ -22,46 +33,25 @@ enum Stage {
     Location,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-struct Mapping {
-    source_stage: Stage,
-    destination_stage: Stage,
-    source_start: u64, 
-    source_end: u64,  
-    destination_start: u64, 
-}
-
-// struct Range {
-//     start: u64,
-//     end: u64,
-// }
-
-fn build_map(
-    input: &str,
-    source_stage: Stage,
-    destination_stage: Stage,
-) -> HashMap<Mapping, Mapping> {
-    let mut map = HashMap::new();
-
-    for line in input.lines() {
-        let parts: Vec<u64> = line 
-            .split_whitespace()
-            .filter_map(|num| num.parse::<u64>().ok())
-            .collect();
-        if parts.len() == 3 {
-            let (destination_start, source_start, length) = (parts[0], parts[1], parts[2]);
-            let source = Mapping {
-                source_stage,
-                destination_stage,
-                source_start,
-                source_end: source_start + length as u64 - 1, 
-                destination_start,
-            };
-            map.insert(source.clone(), source);
-        }
+fn build_map(section: &Section) -> Result<RangeMap<u64>, ParseError> {
+    let mut map = RangeMap::new();
+
+    // Skip the "x-to-y map:" header
+    for line in section.lines().skip(1) {
+        let [destination_start, source_start, length]: [u64; 3] = line.unsigned_array()?;
+        let source_end = source_start
+            .checked_add(length)
+            .filter(|_| destination_start.checked_add(length).is_some())
+            .ok_or_else(|| line.error("the range does not fit into 64 bits"))?;
+        map.try_insert(source_start..source_end, destination_start)
+            .map_err(|other| {
+                line.error(format!(
+                    "the source range overlaps the source range {other:?} of the same map"
+                ))
+            })?;
     }
 
-    map
+    Ok(map)
 }
 
 const SEED_TO_SOIL: &str = "seed-to-soil map";
@@ -72,157 +62,233 @@ const LIGHT_TO_TEMPERATURE: &str = "light-to-temperature map";
 const TEMPERATURE_TO_HUMIDITY: &str = "temperature-to-humidity map";
 const HUMIDITY_TO_LOCATION: &str = "humidity-to-location map";
 
-fn build_all_maps(input: &str) -> (Vec<u32>, Vec<LabeledMap>) {
-    let sections: Vec<&str> = input.split("\n\n").collect();
-    let seeds: Vec<u32> = sections[0]
-        .split(": ")
-        .nth(1)
-        .unwrap_or("")
-        .split_whitespace()
-        .filter_map(|num| num.parse::<u32>().ok())
-        .collect();
-
-    let mut labeled_maps: Vec<LabeledMap> = Vec::new();
-
-    let map_order = vec![
-        (SEED_TO_SOIL, Stage::Seed, Stage::Soil),
-        (SOIL_TO_FERTILIZER, Stage::Soil, Stage::Fertilizer),
-        (FERTILIZER_TO_WATER, Stage::Fertilizer, Stage::Water),
-        (WATER_TO_LIGHT, Stage::Water, Stage::Light),
-        (LIGHT_TO_TEMPERATURE, Stage::Light, Stage::Temperature),
-        (TEMPERATURE_TO_HUMIDITY, Stage::Temperature, Stage::Humidity),
-        (HUMIDITY_TO_LOCATION, Stage::Humidity, Stage::Location),
-    ];
-
-    for (map_name, source_stage, destination_stage) in map_order {
-        if let Some(section) = sections.iter().find(|&s| s.starts_with(map_name)) {
-            let map = build_map(section, source_stage, destination_stage);
-            labeled_maps.push(LabeledMap{
-                map, 
-                source_stage: source_stage,
-                destination_stage: destination_stage,
+impl Almanac {
+    fn parse(input: &str) -> Result<Self, ParseError> {
+        let sections: Vec<Section> = sections(input).collect();
+        let seeds: Vec<u64> = match sections.first().and_then(|s| s.lines().next()) {
+            Some(line) => line.split_label()?.1.unsigned()?,
+            None => vec![],
+        };
+
+        let mut maps: Vec<LabeledMap> = Vec::new();
+
+        let map_order = [
+            (SEED_TO_SOIL, Stage::Seed, Stage::Soil),
+            (SOIL_TO_FERTILIZER, Stage::Soil, Stage::Fertilizer),
+            (FERTILIZER_TO_WATER, Stage::Fertilizer, Stage::Water),
+            (WATER_TO_LIGHT, Stage::Water, Stage::Light),
+            (LIGHT_TO_TEMPERATURE, Stage::Light, Stage::Temperature),
+            (TEMPERATURE_TO_HUMIDITY, Stage::Temperature, Stage::Humidity),
+            (HUMIDITY_TO_LOCATION, Stage::Humidity, Stage::Location),
+        ];
+
+        // Both parts need every map, a missing one would silently map values to themselves.
+        // It is reported at the header of the map before it, or at the seeds.
+        let mut previous_line = sections.first().map_or(1, |s| s.line);
+        for (map_name, source_stage, destination_stage) in map_order {
+            let section = sections
+                .iter()
+                .find(|s| s.text.starts_with(map_name))
+                .ok_or_else(|| ParseError {
+                    line: previous_line,
+                    message: format!("the almanac has no \"{map_name}:\""),
+                })?;
+            previous_line = section.line;
+            maps.push(LabeledMap {
+                map: build_map(section)?,
+                source_stage,
+                destination_stage,
             });
         }
-    }
-
-    (seeds, labeled_maps)
-}
 
-fn traverse(maps: &[LabeledMap], start_value: u64, start_stage: Stage, end_stage: Stage) -> Option<u64> {
-    let mut current_value = start_value;
-    let mut current_stage = start_stage;
-
-    while current_stage != end_stage {
-        if let Some(labeled_map) = maps.iter().find(|map| map.source_stage == current_stage) {
-            let mapping = labeled_map.map.values().find(|&m| 
-                m.source_start <= current_value && current_value <= m.source_end);
-
-            match mapping {
-                Some(m) => {
-                    let offset = current_value - m.source_start;
-                    current_value = m.destination_start + offset;
-                },
-                None => {
-                    // If no mapping is found, assume the value remains the same
-                }
-            }
+        Ok(Almanac { seeds, maps })
+    }
 
+    /// Follows the maps from `start_stage` to `end_stage`. Returns [`None`] if a map is missing,
+    /// which [`Almanac::parse`] rules out.
+    fn traverse(&self, start_value: u64, start_stage: Stage, end_stage: Stage) -> Option<u64> {
+        let mut current_value = start_value;
+        let mut current_stage = start_stage;
+
+        while current_stage != end_stage {
+            let labeled_map = self
+                .maps
+                .iter()
+                .find(|map| map.source_stage == current_stage)?;
+            // Values without a mapping remain the same
+            current_value = labeled_map.map.apply(current_value);
             current_stage = labeled_map.destination_stage;
-        } else {
-            return None; // No map found for the current stage
         }
-    }
 
-    Some(current_value)
-}
+        Some(current_value)
+    }
 
-pub fn part_one(_input: &str) -> Option<u32> {
-    let (seeds, _maps) = build_all_maps(_input);
+    /// The seeds of part two: the seed line read as pairs of start and length.
+    /// Returns [`None`] if the line has an odd number of values or a range does not fit into a `u64`.
+    fn seed_ranges(&self) -> Option<IntervalSet<u64>> {
+        if !self.seeds.len().is_multiple_of(2) {
+            return None;
+        }
 
-    println!("Starting to process seeds: {:?}", seeds);
+        self.seeds
+            .chunks(2)
+            .map(|pair| Some(pair[0]..pair[0].checked_add(pair[1])?))
+            .collect()
+    }
 
-    let mut locations = Vec::new();
+    /// Maps seeds straight to locations. The maps are in stage order, so this composes them.
+    fn seed_to_location(&self) -> RangeMap<u64> {
+        self.maps.iter().fold(RangeMap::new(), |acc, labeled_map| {
+            acc.compose(&labeled_map.map)
+        })
+    }
 
-    for seed in seeds {
-        println!("Processing seed: {}", seed);
-        if let Some(location) = traverse(&_maps, seed as u64, Stage::Seed, Stage::Location) {
-            println!("Seed {} -> Location {}", seed, location);
-            locations.push(location);
-        } else {
-            println!("Traversal failed for seed {}", seed);
-        }
+    /// Returns every seed that ends up in one of `locations`, whether the almanac lists it or not.
+    fn seeds_for(&self, locations: &IntervalSet<u64>) -> IntervalSet<u64> {
+        self.seed_to_location().preimage(locations)
     }
+}
 
-    let min_location = locations.into_iter().min();
-    println!("Minimum location found: {:?}", min_location);
+pub fn part_one(input: &str) -> Option<u64> {
+    let almanac = report(Almanac::parse(input))?;
 
-    Some(1)
+    if let Some(query) = arg_value("--query") {
+        QUERY.call_once(|| print_query(&almanac, &query));
+    }
+
+    almanac
+        .seeds
+        .iter()
+        .map(|&seed| almanac.traverse(seed, Stage::Seed, Stage::Location))
+        .collect::<Option<Vec<u64>>>()?
+        .into_iter()
+        .min()
 }
 
-pub fn part_two(_input: &str) -> Option<u32> {
-    let (seeds, maps) = build_all_maps(_input);
+pub fn part_two(input: &str) -> Option<u64> {
+    let almanac = report(Almanac::parse(input))?;
+    let seed_ranges = almanac.seed_ranges()?;
+
+    almanac.seed_to_location().apply_set(&seed_ranges).min()
+}
 
-    if seeds.len() % 2 != 0 {
-        println!("Seeds vector does not contain a valid number of elements for pairs.");
-        return None;
+/// Parses a `--query` value, either a single location like `46` or a range like `40..50`.
+fn parse_locations(query: &str) -> Option<Range<u64>> {
+    match query.split_once("..") {
+        Some((start, end)) => Some(start.trim().parse().ok()?..end.trim().parse().ok()?),
+        None => {
+            let location: u64 = query.trim().parse().ok()?;
+            Some(location..location.checked_add(1)?)
+        }
     }
+}
 
-    let maps = Arc::new(maps);
-    let total_length: u64 = seeds.chunks(2).map(|pair| pair[1] as u64).sum();
-    let processed_length = Arc::new(Mutex::new(0u64));
-
-    println!("Starting to process seed pairs.");
-
-    let all_locations: Vec<u32> = seeds
-        .par_chunks(2)
-        .flat_map(|seed_pair| {
-            let maps = maps.clone();
-            let processed_length = processed_length.clone();
-
-            if let [start, length] = *seed_pair {
-                (0..length).into_par_iter().filter_map(move |i| {
-                    let seed = start + i;
-                    if let Some(location) = traverse(&maps, seed as u64, Stage::Seed, Stage::Location) {
-                        let mut processed = processed_length.lock().unwrap();
-                        *processed += 1;
-
-                        if *processed % 10000 == 0 {
-                            print!("\rProgress: {:.2}%", (*processed as f64 / total_length as f64) * 100.0);
-                            std::io::stdout().flush().unwrap();
-                        }
-
-                        Some(location as u32) 
-                    } else {
-                        None
-                    }
-                }).collect::<Vec<u32>>()
-            } else {
-                vec![]
-            }
-        })
+/// Prints which seeds land in the locations of `--query`.
+fn print_query(almanac: &Almanac, query: &str) {
+    let Some(locations) = parse_locations(query) else {
+        eprintln!(
+            "Error: expecting a location below {} like 46 or a range like 40..50 for `--query`.",
+            u64::MAX
+        );
+        return;
+    };
+
+    let show = |set: &IntervalSet<u64>| {
+        if set.is_empty() {
+            return "none".to_string();
+        }
+        let ranges: Vec<String> = set
+            .ranges()
+            .iter()
+            .map(|r| format!("{}..{}", r.start, r.end))
+            .collect();
+        ranges.join(", ")
+    };
+
+    let seeds = almanac.seeds_for(&IntervalSet::from_ranges([locations.clone()]));
+    let listed: IntervalSet<u64> = almanac
+        .seeds
+        .iter()
+        .filter(|&&seed| seeds.contains(seed))
+        .filter_map(|&seed| Some(seed..seed.checked_add(1)?))
         .collect();
 
-    println!("\nProcessing complete.");
-
-    let result = all_locations.into_iter().min();
-    println!("Result found: {:?}", result);
-
-    result
+    println!(
+        "Seeds that land in locations {}..{}: {}",
+        locations.start,
+        locations.end,
+        show(&seeds)
+    );
+    println!("  seeds of part one: {}", show(&listed));
+    if let Some(seed_ranges) = almanac.seed_ranges() {
+        println!(
+            "  seeds of part two: {}",
+            show(&seeds.intersection(&seed_ranges))
+        );
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use advent_of_code::parse::lines;
 
     #[test]
     fn test_part_one() {
         let result = part_one(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, None);
+        assert_eq!(result, Some(35));
     }
 
     #[test]
     fn test_part_two() {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, None);
+        assert_eq!(result, Some(46));
+    }
+
+    #[test]
+    fn test_seeds_for_locations() {
+        let almanac =
+            Almanac::parse(&advent_of_code::template::read_file("examples", DAY)).unwrap();
+        let seeds = almanac.seeds_for(&IntervalSet::from_ranges([35..36, 46..47]));
+        assert!(seeds.contains(13));
+        assert!(seeds.contains(82));
+        for seed in seeds.ranges().iter().flat_map(|r| [r.start, r.end - 1]) {
+            let location = almanac.traverse(seed, Stage::Seed, Stage::Location);
+            assert!(matches!(location, Some(35 | 46)), "{seed} -> {location:?}");
+        }
+
+        assert_eq!(parse_locations("46"), Some(46..47));
+        assert_eq!(parse_locations("40..50"), Some(40..50));
+        assert_eq!(parse_locations("x"), None);
+        assert_eq!(parse_locations(&u64::MAX.to_string()), None);
+    }
+
+    #[test]
+    fn test_invalid_almanacs() {
+        let example = advent_of_code::template::read_file("examples", DAY);
+
+        // Without a map, neither part maps values to themselves.
+        let (without_light, _) = example.split_once("light-to-temperature").unwrap();
+        assert_eq!(part_one(without_light), None);
+        assert_eq!(part_two(without_light), None);
+        let water_to_light = lines(without_light)
+            .find(|line| line.text.starts_with("water-to-light"))
+            .unwrap();
+        assert_eq!(
+            Almanac::parse(without_light).err().unwrap().line,
+            water_to_light.number
+        );
+
+        let seeds = format!("seeds: 1 {}\n", u64::MAX);
+        let overflowing = example.replacen(example.lines().next().unwrap(), seeds.trim(), 1);
+        assert!(part_one(&overflowing).is_some());
+        assert_eq!(part_two(&overflowing), None);
+
+        let map = format!("seed-to-soil map:\n0 {} 2", u64::MAX - 1);
+        assert!(Almanac::parse(&example.replacen("seed-to-soil map:", &map, 1)).is_err());
+
+        let overlapping = example.replacen("seed-to-soil map:", "seed-to-soil map:\n0 97 2", 1);
+        // 98..100 on line 5 overlaps the 97..99 inserted before it.
+        assert_eq!(Almanac::parse(&overlapping).err().unwrap().line, 5);
     }
 }
@@ -1,197 +1,183 @@
-advent_of_code::solution!(6);
+use advent_of_code::generate::{Rng, Size};
+use advent_of_code::math::quadratic_negative;
+use advent_of_code::parse::{lines, report, Line, ParseError};
+
+advent_of_code::solution!(6, generate = generate);
+
+/// A race that lasts `duration` ms, with the best distance so far of `record` mm.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 struct Race {
-    race_max_time:u32,
-    race_distance_record: u64,
-    race_record_speed: u32,
-    max_possible_speed: Option<u32>,
-    max_possible_distance: u64,
+    duration: u64,
+    record: u64,
 }
 
-fn calculate_maximum_distance(race_duration: u64) -> u64 {
-    if race_duration == 0 {
-        return 0;
+impl Race {
+    /// Counts the hold times x in 0..=T that beat the record, i.e. with x·(T - x) > D.
+    fn winning_hold_times(self) -> u64 {
+        // x·(T - x) > D  <=>  x² - T·x + D < 0, whose solutions lie strictly between 0 and T.
+        quadratic_negative(1, -i128::from(self.duration), i128::from(self.record)).map_or(
+            0,
+            |range| {
+                u64::try_from(range.end() - range.start() + 1)
+                    .expect("there are at most as many hold times as milliseconds")
+            },
+        )
     }
+}
 
+fn calculate_maximum_distance(race_duration: u64) -> u64 {
+    // x * (T - x) is largest in the middle of the race.
     let penalty_time = race_duration / 2;
-    let distance1 = penalty_time.checked_mul(race_duration.checked_sub(penalty_time).unwrap_or(0)).unwrap_or(u64::MAX);
-
-    // Check the adjacent value as well
-    let penalty_time_adjacent = if race_duration % 2 == 0 { penalty_time.checked_sub(1).unwrap_or(0) } else { penalty_time.checked_add(1).unwrap_or(u64::MAX) };
-    let distance2 = penalty_time_adjacent.checked_mul(race_duration.checked_sub(penalty_time_adjacent).unwrap_or(0)).unwrap_or(u64::MAX);
+    penalty_time * (race_duration - penalty_time)
+}
 
-    std::cmp::max(distance1, distance2)
+/// Parses the "Time:" and "Distance:" rows, reading each row with `parse_row`.
+fn parse_rows<T>(
+    input: &str,
+    parse_row: impl Fn(Line) -> Result<T, ParseError>,
+) -> Result<(T, T), ParseError> {
+    let mut lines = lines(input);
+    let mut row = |number: usize, label: &str| match lines.next() {
+        Some(line) => parse_row(line.split_label()?.1),
+        None => Err(ParseError {
+            line: number,
+            message: format!("expected a \"{label}:\" row"),
+        }),
+    };
+    Ok((row(1, "Time")?, row(2, "Distance")?))
 }
 
-pub fn part_one(_input: &str) -> Option<u32> {
-    let mut lines = _input.lines();
-
-    let times: Vec<u32> = lines
-        .next()
-        .unwrap_or("")
-        .split_whitespace()
-        .skip(1)
-        .filter_map(|s| s.parse::<u32>().ok())
-        .collect();
-
-    let distances: Vec<u64> = lines
-        .next()
-        .unwrap_or("")
-        .split_whitespace()
-        .skip(1)
-        .filter_map(|s| s.parse::<u64>().ok())
-        .collect();    
-    
-    let races: Vec<Race> = times
-        .into_iter()
-        .zip(distances.into_iter())
-        .filter_map(|(race_duration, distance)| {
-            calculate_penalty_time(race_duration, distance).map(|record_speed| {
-                let max_possible_distance = calculate_maximum_distance(race_duration as u64);
-                let max_possible_speed = calculate_penalty_time(race_duration, max_possible_distance);
-
-                Race {
-                    race_max_time: race_duration, 
-                    race_distance_record: distance,
-                    race_record_speed: record_speed,
-                    max_possible_distance, 
-                    max_possible_speed
-                }
-            })
-        })
-    .collect();
-
-let mut sum: u32 = 1;
-for race in races {
-        let mut valid_speed_options_count = 0;
-        println!("~ Analyzing Race: Record calculated speed: {}, Max possible distance: {}, Max possible speed: {:?}", 
-                 race.race_record_speed, 
-                 race.max_possible_distance, 
-                 race.max_possible_speed);
-    
-        for penalty_time in 0..race.race_max_time {
-            let speed = penalty_time;
-            let distance = (speed * (race.race_max_time - penalty_time)) as u64;
-    
-           println!("Penalty Time: {}, Speed: {}, Distance: {}", penalty_time, speed, distance);
-    
-            if distance > race.race_distance_record {
-                valid_speed_options_count += 1;
-                println!("Valid option found: Penalty Time: {}, Speed: {}, Distance: {}", penalty_time, speed, distance);
-            }
-        }
-        sum *= valid_speed_options_count;
+/// Parses the races of part one, one per column. Fails unless both rows have as many columns.
+fn parse_races(input: &str) -> Result<Vec<Race>, ParseError> {
+    let (times, distances): (Vec<u64>, Vec<u64>) = parse_rows(input, |row| row.unsigned())?;
+    if times.len() != distances.len() {
+        return Err(ParseError {
+            line: 2,
+            message: format!(
+                "expected {} distances, one per race, but found {}",
+                times.len(),
+                distances.len()
+            ),
+        });
     }
-    
-    println!("Total valid option sum: {}", sum);
-    Some(sum)
-    
-    
+    Ok(times
+        .into_iter()
+        .zip(distances)
+        .map(|(duration, record)| Race { duration, record })
+        .collect())
 }
 
-fn calculate_penalty_time(race_duration: u32, distance: u64) -> Option<u32> {
-    let a: i64 = -1;
-    let b: i64 = race_duration as i64;
-    let c: i64 = -(distance as i64);
-
-    let discriminant = b.pow(2) - 4 * a * c;
-
-    if discriminant < 0 {
-        None
-    } else {
-        // Calculate both possible solutions
-        let sqrt_discriminant = (discriminant as f64).sqrt();
-        let penalty_time1 = ((-b as f64) + sqrt_discriminant) / (2.0 * a as f64);
-        let penalty_time2 = ((-b as f64) - sqrt_discriminant) / (2.0 * a as f64);
+/// Parses the single race of part two, whose numbers are spread over the columns.
+/// Fails if a number does not fit into a `u64`.
+fn parse_kerned_race(input: &str) -> Result<Race, ParseError> {
+    let (duration, record) = parse_rows(input, |row| {
+        let digits: String = row.text.split_whitespace().collect();
+        digits
+            .parse()
+            .map_err(|_| row.error(format!("\"{digits}\" is not a valid 64-bit number")))
+    })?;
+    Ok(Race { duration, record })
+}
 
-        let valid_penalty_times: Vec<u32> = [penalty_time1, penalty_time2]
-            .iter()
-            .filter_map(|&time| {
-                let time = time.round() as i64;
-                if time >= 0 && time <= race_duration as i64 {
-                    Some(time as u32)
-                } else {
-                    None
-                }
-            })
-            .collect();
-        valid_penalty_times.into_iter().max()
-    }
+/// Multiplies the ways to win each race. Returns [`None`] if the product overflows a `u64`.
+pub fn part_one(input: &str) -> Option<u64> {
+    report(parse_races(input))?
+        .into_iter()
+        .map(Race::winning_hold_times)
+        .try_fold(1, u64::checked_mul)
 }
 
-pub fn part_two(_input: &str) -> Option<u32> {
-    let mut lines = _input.lines();
-
-    let times: Vec<u32> = lines
-        .next()
-        .unwrap_or("")
-        .split_whitespace()
-        .skip(1)
-        .filter_map(|s| s.parse::<u32>().ok())
-        .collect();
-
-    let distances: Vec<u32> = lines
-        .next()
-        .unwrap_or("")
-        .split_whitespace()
-        .skip(1)
-        .filter_map(|s| s.parse::<u32>().ok())
-        .collect();    
-
-        let time_str = times.iter().map(|&num| num.to_string()).collect::<String>();
-        let race_duration: u64 = time_str.parse().unwrap_or(0);
-
-        let distance_str = distances.iter().map(|&num| num.to_string()).collect::<String>();
-        let distance_record: u64 = distance_str.parse().unwrap_or(0);
-        
-        println!("Race Duration: {}, Distance Record: {}", race_duration, distance_record);
-
-        let record_speed = calculate_penalty_time(race_duration as u32, distance_record);
-        let max_possible_distance = calculate_maximum_distance(race_duration);
-        let max_possible_speed = calculate_penalty_time(race_duration as u32, max_possible_distance);
-        
-        let race = Race {
-            race_max_time: race_duration as u32, 
-            race_distance_record: distance_record,
-            race_record_speed: record_speed.unwrap_or(0),
-            max_possible_distance, 
-            max_possible_speed
-        };
-
-    let mut valid_speed_options_count = 0;
-    
-    println!("~ Analyzing Race: Record calculated speed: {}, Max possible distance: {}, Max possible speed: {:?}", 
-             race.race_record_speed, 
-             race.max_possible_distance, 
-             race.max_possible_speed);
-
-
-    for penalty_time in 0..race.race_max_time {
-        let speed: u64 = penalty_time.into();
-        let distance: u64 = speed * (race.race_max_time as u64 - penalty_time as u64);
-
-        if distance > race.race_distance_record.into() {
-            valid_speed_options_count += 1;
-        }
-    }
+pub fn part_two(input: &str) -> Option<u64> {
+    Some(report(parse_kerned_race(input))?.winning_hold_times())
+}
 
+/// Generates races with two digit durations, so that the concatenated race of part two stays small.
+/// Every record can be beaten.
+pub fn generate(seed: u64, size: Size) -> String {
+    let mut rng = Rng::new(seed);
+    let races = size.pick(3, 4, 4);
+    let durations = size.pick(7..31, 30..61, 60..100);
+
+    let (times, distances): (Vec<u64>, Vec<u64>) = (0..races)
+        .map(|_| {
+            let time = rng.range(durations.clone());
+            (time, rng.range(0..calculate_maximum_distance(time)))
+        })
+        .unzip();
 
-    Some(valid_speed_options_count)
+    let row = |values: &[u64]| values.iter().map(|v| format!("{v:>5}")).collect::<String>();
+    format!("Time:    {}\nDistance:{}\n", row(&times), row(&distances))
 }
 
-
 #[cfg(test)]
 mod tests {
     use super::*;
+    use advent_of_code::generate::Property;
+
+    /// Tries every penalty time.
+    fn part_one_naive(input: &str) -> Option<u64> {
+        let product = report(parse_races(input))?
+            .iter()
+            .map(|race| {
+                (0..=race.duration)
+                    .filter(|x| x * (race.duration - x) > race.record)
+                    .count()
+            })
+            .product::<usize>();
+        u64::try_from(product).ok()
+    }
 
     #[test]
     fn test_part_one() {
         let result = part_one(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, None);
+        assert_eq!(result, Some(288));
     }
 
     #[test]
     fn test_part_two() {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, None);
+        assert_eq!(result, Some(71503));
+    }
+
+    #[test]
+    fn test_large_races() {
+        let race = |duration, record| Race { duration, record }.winning_hold_times();
+        assert_eq!(race(u64::MAX, 0), u64::MAX - 1);
+        assert_eq!(race(u64::MAX, u64::MAX), u64::MAX - 3);
+        // Only holding for half of the race wins, where f64 roots are off by one.
+        let t = 1 << 32;
+        assert_eq!(race(t, t / 2 * (t / 2) - 1), 1);
+        assert_eq!(race(t, t / 2 * (t / 2)), 0);
+        assert_eq!(race(0, 0), 0);
+    }
+
+    #[test]
+    fn test_overflow() {
+        // 4294967296 ms leave 4294967295 ways to win, and their cube does not fit into a u64.
+        let input = "Time: 4294967296 4294967296 4294967296\nDistance: 0 0 0\n";
+        assert_eq!(part_one(input), None);
+        assert_eq!(part_two("Time: 9999999999 9999999999\nDistance: 1\n"), None);
+        assert!(parse_kerned_race("Time: 7\n").is_err());
+    }
+
+    #[test]
+    fn test_column_counts() {
+        let error = parse_races("Time: 7 15 30\nDistance: 9 40\n").unwrap_err();
+        assert_eq!(error.line, 2);
+        assert_eq!(part_one("Time: 7 15\nDistance: 9 40 200\n"), None);
+    }
+
+    #[test]
+    fn test_generated_inputs() {
+        Property::new()
+            .run(
+                generate,
+                &[("part_one", part_one), ("naive", part_one_naive)],
+            )
+            .unwrap();
+        Property::new()
+            .sizes(&[Size::Large])
+            .run(generate, &[("part_two", part_two)])
+            .unwrap();
     }
 }
@@ -1,6 +1,22 @@
-use std::collections::HashMap;
-advent_of_code::solution!(7);
-#[derive(PartialEq, Eq, PartialOrd, Ord, Debug)]
+use std::cmp::Ordering;
+use std::collections::HashSet;
+use std::sync::Once;
+
+use advent_of_code::generate::{Rng, Size};
+use advent_of_code::grid::Colour;
+use advent_of_code::parse::{lines, report, Line, ParseError};
+use advent_of_code::template::{arg_value, has_flag};
+use advent_of_code::viz::{self, Table};
+advent_of_code::solution!(7, generate = generate, variants = [2 => [part_two_bruteforce]]);
+
+/// The card symbols from the weakest to the strongest, as ordered without jokers.
+const SYMBOLS: &[u8; 13] = b"23456789TJQKA";
+
+/// The ranked tables of `--table` are only printed for the first run of each part, not for every
+/// benchmark sample.
+static TABLES: [Once; 2] = [Once::new(), Once::new()];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 enum HandRank {
     HighCard,
     OnePair,
@@ -11,179 +27,376 @@ enum HandRank {
     FiveOfAKind,
 }
 
-fn card_value(card: char, card_values: [u8; 13]) -> u8 {
-    match card {
-        'A' => card_values[0],
-        'K' => card_values[1],
-        'Q' => card_values[2],
-        'J' => card_values[3],
-        'T' => card_values[4],
-        '9' => card_values[5],
-        '8' => card_values[6],
-        '7' => card_values[7],
-        '6' => card_values[8],
-        '5' => card_values[9],
-        '4' => card_values[10],
-        '3' => card_values[11],
-        '2' => card_values[12],
-        _ => 0,
-    }
-}
-
-fn wrangle_hand(hand: &str, card_values: [u8; 13]) -> (HandRank, [u8; 5]) {
-    let mut counts = HashMap::new();
-    let card_values_vec: Vec<u8> = hand.chars().map(|c| card_value(c, card_values)).collect();
-
-    // Convert Vec<u8> to [u8; 5]
-    let card_tuple = match card_values_vec.try_into() {
-        Ok(arr) => arr,
-        Err(_) => panic!("Hand does not have 5 cards"),
-    };
-    for card in hand.chars() {
-        let value = card_value(card, card_values);
-        *counts.entry(value).or_insert(0) += 1;
-    }
-
-    let mut pair_count = 0;
-    let mut three_count = 0;
-    let mut four_count = 0;
-    let mut five_count = 0;
-
-    for &count in counts.values() {
-        match count {
-            2 => pair_count += 1,
-            3 => three_count += 1,
-            4 => four_count += 1,
-            5 => five_count += 1,
-            _ => {}
-        }
+/// A card, stored as its position in [`SYMBOLS`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Card(u8);
+
+impl Card {
+    const JACK: Card = Card(9);
+
+    fn parse(symbol: char) -> Option<Self> {
+        let index = SYMBOLS.iter().position(|&s| char::from(s) == symbol)?;
+        Some(Card(index as u8))
+    }
+
+    fn index(self) -> usize {
+        usize::from(self.0)
     }
 
-    match (five_count, four_count, three_count, pair_count) {
-        (1, _, _, _) => (HandRank::FiveOfAKind, card_tuple),
-        (_, 1, _, _) => (HandRank::FourOfAKind, card_tuple),
-        (_, _, 1, 1) => (HandRank::FullHouse, card_tuple),
-        (_, _, 1, _) => (HandRank::ThreeOfAKind, card_tuple),
-        (_, _, _, 2) => (HandRank::TwoPair, card_tuple),
-        (_, _, _, 1) => (HandRank::OnePair, card_tuple),
-        _ => (HandRank::HighCard, card_tuple),
+    fn symbol(self) -> char {
+        char::from(SYMBOLS[self.index()])
     }
 }
 
-fn log_game(game: Vec<(HandRank, [u8; 5], u32)>) {
-    for (index, hand) in game.iter().enumerate() {
-        let rank = &hand.0;
-        let color = hand_rank_color(&rank);
-        let winnings = (index as u32 + 1) * hand.2;
-        println!(
-            "{}: {}{:?} - Hand: {:?} - Bet: {:?} - Winnings: {}{} ",
-            index, color, rank, hand.1, hand.2, winnings, "\x1b[0m"
-        );
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Hand {
+    cards: [Card; 5],
+    bid: u32,
+}
+
+impl Hand {
+    /// Parses a `32T3K 765` line.
+    fn parse(line: Line) -> Result<Self, ParseError> {
+        let (cards, bid) = line
+            .text
+            .split_once(' ')
+            .ok_or_else(|| line.error("expected a hand and a bid"))?;
+
+        let cards: Vec<Card> = cards
+            .chars()
+            .map(|c| Card::parse(c).ok_or_else(|| line.error(format!("'{c}' is not a card"))))
+            .collect::<Result<_, _>>()?;
+        let count = cards.len();
+        let cards = cards
+            .try_into()
+            .map_err(|_| line.error(format!("expected 5 cards, found {count}")))?;
+        let [bid] = line.with_text(bid).unsigned_array()?;
+
+        Ok(Hand { cards, bid })
+    }
+
+    fn symbols(&self) -> String {
+        self.cards.iter().map(|card| card.symbol()).collect()
     }
 }
 
-// for console debugging with colour coding
-fn hand_rank_color(rank: &HandRank) -> &str {
-    match rank {
-        HandRank::FiveOfAKind => "\x1b[35m",  // Magenta
-        HandRank::FourOfAKind => "\x1b[34m",  // Blue
-        HandRank::FullHouse => "\x1b[32m",    // Green
-        HandRank::ThreeOfAKind => "\x1b[36m", // Cyan
-        HandRank::TwoPair => "\x1b[33m",      // Yellow
-        HandRank::OnePair => "\x1b[31m",      // Red
-        HandRank::HighCard => "\x1b[37m",     // White
-    }
-}
-
-fn process_game_input(
-    game: &str,
-    card_values: [u8; 13],
-    use_wildcard: bool,
-) -> Vec<(HandRank, [u8; 5], u32)> {
-    let mut hands = Vec::new();
-    for line in game.lines() {
-        let parts: Vec<&str> = line.split_whitespace().collect();
-        if parts.len() == 2 {
-            if let Ok(bet) = parts[1].parse::<u32>() {
-                hands.push((parts[0].to_string(), bet));
-            }
-        }
+fn parse_hands(input: &str) -> Result<Vec<Hand>, ParseError> {
+    lines(input)
+        .filter(|line| !line.text.trim().is_empty())
+        .map(Hand::parse)
+        .collect()
+}
+
+/// How hands are ranked: the order of the cards that break ties, and which card is wild.
+trait RankingRule {
+    /// The strength of `card` when breaking ties between hands of the same rank.
+    fn strength(&self, card: Card) -> u8;
+
+    /// The card that may stand for any other card, if any.
+    fn wildcard(&self) -> Option<Card> {
+        None
+    }
+
+    /// The best rank of `cards` with the wildcard standing for any card.
+    fn rank(&self, cards: &[Card; 5]) -> HandRank {
+        counted_rank(cards, self.wildcard())
+    }
+
+    /// Orders hands by their rank first, then card by card.
+    fn key(&self, hand: &Hand) -> (HandRank, [u8; 5]) {
+        (
+            self.rank(&hand.cards),
+            hand.cards.map(|card| self.strength(card)),
+        )
+    }
+}
+
+/// The rules of part one: cards are ranked as in [`SYMBOLS`], nothing is wild.
+struct Standard;
+
+impl RankingRule for Standard {
+    fn strength(&self, card: Card) -> u8 {
+        card.0
     }
+}
 
-    let mut tuple_hands: Vec<(HandRank, [u8; 5], u32)> = Vec::new();
+/// The rules of part two: jacks are jokers, which are wild but the weakest card on their own.
+struct JokersWild;
 
-    for hand in hands {
-        let (original_hand_rank, original_hand_cards) = wrangle_hand(&hand.0, card_values);
-        let mut best_rank = original_hand_rank;
+impl RankingRule for JokersWild {
+    fn strength(&self, card: Card) -> u8 {
+        match card.0.cmp(&Card::JACK.0) {
+            Ordering::Less => card.0 + 1,
+            Ordering::Equal => 0,
+            Ordering::Greater => card.0,
+        }
+    }
 
-        if use_wildcard {
-            let card_symbols = [
-                "A", "K", "Q", "T", "9", "8", "7", "6", "5", "4", "3", "2", "J",
-            ];
+    fn wildcard(&self) -> Option<Card> {
+        Some(Card::JACK)
+    }
+}
 
-            let has_wildcard = hand.0.contains('J');
+/// Part two, ranking every hand by trying each card for its jokers.
+struct SubstitutedJokers;
 
-            if has_wildcard {
-                for wildcard_value in card_symbols {
-                    let modified_hand = hand.0.replace('J', &wildcard_value);
-                    let (rank, _) = wrangle_hand(&modified_hand, card_values);
+impl RankingRule for SubstitutedJokers {
+    fn strength(&self, card: Card) -> u8 {
+        JokersWild.strength(card)
+    }
 
-                    if rank > best_rank {
-                        best_rank = rank;
+    fn rank(&self, cards: &[Card; 5]) -> HandRank {
+        (0..SYMBOLS.len() as u8)
+            .map(|substitute| {
+                let cards = cards.map(|card| {
+                    if card == Card::JACK {
+                        Card(substitute)
+                    } else {
+                        card
                     }
-                }
+                });
+                counted_rank(&cards, None)
+            })
+            .max()
+            .unwrap_or(HandRank::HighCard)
+    }
+}
+
+/// Cards in the order given by `--order`, from the strongest to the weakest.
+struct CustomOrder {
+    strengths: [u8; 13],
+    wildcard: Option<Card>,
+}
+
+impl CustomOrder {
+    /// Reads an order like `AKQT98765432J`, which has to name every card once.
+    fn parse(order: &str, wildcard: Option<Card>) -> Result<Self, String> {
+        let mut strengths = [None; 13];
+        for (strength, symbol) in (0..).zip(order.chars().rev()) {
+            let card = Card::parse(symbol).ok_or_else(|| format!("'{symbol}' is not a card"))?;
+            if strengths[card.index()].replace(strength).is_some() {
+                return Err(format!("'{symbol}' is given twice"));
             }
         }
 
-        // store the rank / card tuple (as the originals regardless of wildcard replacements) / bet value
-        tuple_hands.push((best_rank, original_hand_cards, hand.1));
+        match strengths.iter().position(Option::is_none) {
+            Some(missing) => Err(format!("'{}' is missing", Card(missing as u8).symbol())),
+            None => Ok(CustomOrder {
+                strengths: strengths.map(Option::unwrap_or_default),
+                wildcard,
+            }),
+        }
+    }
+}
+
+impl RankingRule for CustomOrder {
+    fn strength(&self, card: Card) -> u8 {
+        self.strengths[card.index()]
     }
 
-    tuple_hands
+    fn wildcard(&self) -> Option<Card> {
+        self.wildcard
+    }
 }
 
-pub fn part_one(input: &str) -> Option<u32> {
-    const CARD_VALUES: [u8; 13] = [14, 13, 12, 11, 10, 9, 8, 7, 6, 5, 4, 3, 2];
-    let mut games_hands = process_game_input(input, CARD_VALUES, false);
-    games_hands.sort();
-    let mut sum: u32 = 0;
+/// The best rank of `cards` when `wildcard` may stand for any card. Wildcards always do best
+/// joining the most frequent other card.
+fn counted_rank(cards: &[Card; 5], wildcard: Option<Card>) -> HandRank {
+    let mut counts = [0u8; 13];
+    let mut wildcards = 0;
+    for &card in cards {
+        if Some(card) == wildcard {
+            wildcards += 1;
+        } else {
+            counts[card.index()] += 1;
+        }
+    }
+
+    counts.sort_unstable_by(|a, b| b.cmp(a));
+    counts[0] += wildcards;
+
+    match (counts[0], counts[1]) {
+        (5, _) => HandRank::FiveOfAKind,
+        (4, _) => HandRank::FourOfAKind,
+        (3, 2) => HandRank::FullHouse,
+        (3, _) => HandRank::ThreeOfAKind,
+        (2, 2) => HandRank::TwoPair,
+        (2, _) => HandRank::OnePair,
+        _ => HandRank::HighCard,
+    }
+}
+
+/// Ranks the hands from the weakest to the strongest and adds up their winnings.
+/// With `--order <cards>`, ties are broken in that order instead, keeping the wildcard of `rule`.
+fn total_winnings(input: &str, part: u8, rule: &dyn RankingRule) -> Option<u32> {
+    let custom;
+    let rule = match arg_value("--order") {
+        Some(order) => match CustomOrder::parse(&order, rule.wildcard()) {
+            Ok(order) => {
+                custom = order;
+                &custom
+            }
+            Err(e) => {
+                eprintln!("Error: invalid order: {e}");
+                return None;
+            }
+        },
+        None => rule,
+    };
+
+    let mut hands: Vec<((HandRank, [u8; 5]), Hand)> = report(parse_hands(input))?
+        .into_iter()
+        .map(|hand| (rule.key(&hand), hand))
+        .collect();
+    hands.sort_unstable_by_key(|(key, _)| *key);
+
+    let sum = (1..)
+        .zip(&hands)
+        .map(|(rank, (_, hand))| rank * hand.bid)
+        .sum();
 
-    for (index, hand) in games_hands.iter().enumerate() {
-        let winnings = (index as u32 + 1) * hand.2;
-        sum += winnings;
+    let show_table = has_flag("--table");
+    if show_table || viz::enabled() {
+        let table = ranked_table(&hands);
+        if show_table {
+            TABLES[usize::from(part - 1)].call_once(|| println!("{table}"));
+        }
+        if viz::enabled() {
+            viz::record(table.to_frame());
+        }
     }
-    // log_game(games_hands);
+
     Some(sum)
 }
 
+/// Lists the hands from the weakest to the strongest, coloured by their rank.
+fn ranked_table(hands: &[((HandRank, [u8; 5]), Hand)]) -> Table {
+    let mut table = Table::new(["Rank", "Hand", "Type", "Bid", "Winnings"]);
+
+    for (rank, ((hand_rank, _), hand)) in (1..).zip(hands) {
+        table.add_row(
+            [
+                rank.to_string(),
+                hand.symbols(),
+                format!("{hand_rank:?}"),
+                hand.bid.to_string(),
+                (rank * hand.bid).to_string(),
+            ],
+            hand_rank_color(*hand_rank),
+        );
+    }
+
+    table
+}
+
+fn hand_rank_color(rank: HandRank) -> Option<Colour> {
+    match rank {
+        HandRank::FiveOfAKind => Some(Colour::Magenta),
+        HandRank::FourOfAKind => Some(Colour::Blue),
+        HandRank::FullHouse => Some(Colour::Green),
+        HandRank::ThreeOfAKind => Some(Colour::Cyan),
+        HandRank::TwoPair => Some(Colour::Yellow),
+        HandRank::OnePair => Some(Colour::Red),
+        HandRank::HighCard => None,
+    }
+}
+
+pub fn part_one(input: &str) -> Option<u32> {
+    total_winnings(input, 1, &Standard)
+}
+
 pub fn part_two(input: &str) -> Option<u32> {
-    const CARD_VALUES: [u8; 13] = [14, 13, 12, 1, 10, 9, 8, 7, 6, 5, 4, 3, 2];
-    let mut games_hands = process_game_input(input, CARD_VALUES, true);
-    games_hands.sort();
-    let mut sum: u32 = 0;
+    total_winnings(input, 2, &JokersWild)
+}
+
+/// Tries all 13 substitutions for the jokers of every hand.
+pub fn part_two_bruteforce(input: &str) -> Option<u32> {
+    total_winnings(input, 2, &SubstitutedJokers)
+}
+
+/// Generates distinct hands with bets up to 1000. Jokers are more frequent than other cards,
+/// so that part two sees hands with several of them.
+pub fn generate(seed: u64, size: Size) -> String {
+    const CARDS: &[u8] = b"AKQJJJT98765432";
+    let mut rng = Rng::new(seed);
+    let mut hands = HashSet::new();
+    let mut output = String::new();
 
-    for (index, hand) in games_hands.iter().enumerate() {
-        let winnings = (index as u32 + 1) * hand.2;
-        sum += winnings;
+    while hands.len() < size.pick(10, 100, 1000) {
+        let hand: String = (0..5).map(|_| char::from(*rng.choose(CARDS))).collect();
+        if hands.insert(hand.clone()) {
+            output += &format!("{hand} {}\n", rng.range(1..1001));
+        }
     }
 
-    // log_game(games_hands);
-    Some(sum)
+    output
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use advent_of_code::generate::Property;
 
     #[test]
     fn test_part_one() {
         let result = part_one(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, None);
+        assert_eq!(result, Some(6440));
     }
 
     #[test]
     fn test_part_two() {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, None);
+        assert_eq!(result, Some(5905));
+    }
+
+    #[test]
+    fn test_hand_ranks() {
+        let cards = |hand: &str| {
+            Hand::parse(lines(&format!("{hand} 1")).next().unwrap())
+                .unwrap()
+                .cards
+        };
+        assert_eq!(Standard.rank(&cards("QJJQ2")), HandRank::TwoPair);
+        assert_eq!(JokersWild.rank(&cards("QJJQ2")), HandRank::FourOfAKind);
+        assert_eq!(JokersWild.rank(&cards("JJJJJ")), HandRank::FiveOfAKind);
+        assert_eq!(JokersWild.rank(&cards("2345J")), HandRank::OnePair);
+        assert_eq!(JokersWild.rank(&cards("2233J")), HandRank::FullHouse);
+        // A lone joker is the weakest card.
+        assert!(
+            JokersWild.key(&Hand::parse(lines("JKKK2 1").next().unwrap()).unwrap())
+                < JokersWild.key(&Hand::parse(lines("QQQQ2 1").next().unwrap()).unwrap())
+        );
+
+        assert!(parse_hands("32T3 765\n").is_err());
+        assert!(parse_hands("32T3X 765\n").is_err());
+    }
+
+    #[test]
+    fn test_custom_order() {
+        // The order of part two, given explicitly.
+        let order = CustomOrder::parse("AKQT98765432J", Some(Card::JACK)).unwrap();
+        let hands = parse_hands(&advent_of_code::template::read_file("examples", DAY)).unwrap();
+        for hand in &hands {
+            assert_eq!(order.key(hand), JokersWild.key(hand));
+        }
+
+        assert!(CustomOrder::parse("AKQT98765432", None).is_err());
+        assert!(CustomOrder::parse("AKQT98765432JJ", None).is_err());
+        assert!(CustomOrder::parse("AKQT98765432X", None).is_err());
+    }
+
+    #[test]
+    fn test_generated_inputs() {
+        let property = Property::new().sizes(&Size::ALL).seeds(0..8);
+        property.run(generate, &[("part_one", part_one)]).unwrap();
+        property
+            .run(
+                generate,
+                &[
+                    ("part_two", part_two),
+                    ("part_two_bruteforce", part_two_bruteforce),
+                ],
+            )
+            .unwrap();
     }
-}
\ No newline at end of file
+}
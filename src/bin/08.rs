@@ -1,188 +1,90 @@
-use std::cell::RefCell;
-use std::collections::HashMap;
-use std::rc::Rc;
+use advent_of_code::cycles::{brent, first_common_hit, Hits};
+use advent_of_code::graph::{Graph, GraphBuilder, NodeId};
 
 advent_of_code::solution!(8);
 
-struct Node<'a> {
-    value: &'a str,
-    left: Option<Rc<RefCell<Node<'a>>>>,
-    right: Option<Rc<RefCell<Node<'a>>>>,
+/// The network from the puzzle. The edges of every node are stored left first, then right.
+struct Network {
+    directions: Vec<char>,
+    graph: Graph,
 }
 
-impl<'a> Node<'a> {
-    fn new(value: &'a str) -> Rc<RefCell<Self>> {
-        Rc::new(RefCell::new(Node {
-            value,
-            left: None,
-            right: None,
-        }))
-    }
+/// A position in the walk: the current node and the index of the next direction.
+type State = (NodeId, usize);
 
-    fn set_left(&mut self, node: Rc<RefCell<Node<'a>>>) {
-        self.left = Some(node);
+impl Network {
+    fn step(&self, &(node, direction): &State) -> State {
+        // Every node has a left and a right edge, see `parse_input`.
+        let edges = self.graph.neighbours(node);
+        let next = if self.directions[direction] == 'L' {
+            edges[0]
+        } else {
+            edges[1]
+        };
+        (next, (direction + 1) % self.directions.len())
     }
 
-    fn set_right(&mut self, node: Rc<RefCell<Node<'a>>>) {
-        self.right = Some(node);
+    /// Returns the steps at which the walk from `start` is on a node matching `is_end`.
+    fn hits(&self, start: NodeId, is_end: impl Fn(NodeId) -> bool) -> Hits {
+        let step = |state: &State| self.step(state);
+        brent((start, 0), step).hits((start, 0), step, |&(node, _)| is_end(node))
     }
 }
 
-struct Graph<'a> {
-    root: Option<Rc<RefCell<Node<'a>>>>,
-}
-
-impl<'a> Graph<'a> {
-    fn new() -> Self {
-        Graph { root: None }
-    }
-
-    fn set_root(&mut self, node: Rc<RefCell<Node<'a>>>) {
-        self.root = Some(node);
-    }
-}
+fn parse_input(input: &str) -> Option<Network> {
+    let mut lines = input.lines();
+    let directions: Vec<char> = lines.next()?.trim().chars().collect();
 
-fn gcd(a: u64, b: u64) -> u64 {
-    if b == 0 {
-        a
-    } else {
-        gcd(b, a % b)
+    if directions.is_empty() {
+        return None;
     }
-}
 
-fn lcm(a: u64, b: u64) -> u64 {
-    a / gcd(a, b) * b
-}
+    let mut builder = GraphBuilder::new();
 
-fn parse_input(input: &str) -> (Vec<char>, Graph<'_>, HashMap<String, Rc<RefCell<Node<'_>>>>) {
-    let mut lines = input.lines();
-    let directions: Vec<char> = lines.next().unwrap_or("").chars().collect();
-
-    let mut graph = Graph::new();
-    let mut nodes = HashMap::new();
-
-    // First, ensure all nodes are created and inserted into the HashMap
-    for line in lines {
-        let parts: Vec<&str> = line.split(" = ").collect();
-        if parts.len() == 2 {
-            let node_name = parts[0].trim();
-            nodes
-                .entry(node_name.to_string())
-                .or_insert_with(|| Node::new(node_name));
-        }
-    }
+    for line in lines.filter(|line| !line.trim().is_empty()) {
+        let (node_name, left, right) =
+            advent_of_code::scan!("{} = ({}, {})", line => str, str, str).ok()?;
 
-    // Then, set up the connections
-    for line in input.lines().skip(1) {
-        // skip the directions line
-        let parts: Vec<&str> = line.split(" = ").collect();
-        if parts.len() == 2 {
-            let node_name = parts[0].trim();
-            let connections: Vec<&str> = parts[1]
-                .trim_matches(|patch| patch == '(' || patch == ')')
-                .split(",")
-                .map(str::trim)
-                .collect();
-
-            if let Some(current_node) = nodes.get(node_name) {
-                for (i, connection_name) in connections.iter().enumerate() {
-                    if let Some(connected_node) = nodes.get(*connection_name) {
-                        if i == 0 {
-                            current_node.borrow_mut().set_left(connected_node.clone());
-                        } else {
-                            current_node.borrow_mut().set_right(connected_node.clone());
-                        }
-                    }
-                }
-            }
-        }
+        builder.add_edge(node_name, left, ());
+        builder.add_edge(node_name, right, ());
     }
 
-    // Set the root node
-    if let Some(root) = nodes.get("AAA") {
-        graph.set_root(root.clone());
+    let graph = builder.build();
+    // Nodes that are only referenced, or defined twice, can not be walked.
+    if graph.nodes().any(|node| graph.neighbours(node).len() != 2) {
+        return None;
     }
 
-    (directions, graph, nodes)
+    Some(Network { directions, graph })
 }
 
-pub fn part_one(_input: &str) -> Option<u32> {
-    let (directions, graph, _) = parse_input(_input);
-    let root_node = graph.root.clone();
-    let mut current_node = graph.root.clone();
-    let mut directions_index = 0;
-    let mut steps = 0;
-
-    while let Some(node) = &current_node {
-        // traverse the graph according to the directions infinitely until we find the destination node "ZZZ"
-        if node.borrow().value == "ZZZ" {
-            return Some(steps);
-        }
-
-        steps += 1;
-        let next_node = if directions[directions_index] == 'L' {
-            node.borrow().left.clone()
-        } else {
-            node.borrow().right.clone()
-        };
-        current_node = next_node.or_else(|| root_node.clone());
-        directions_index = (directions_index + 1) % directions.len();
-    }
+pub fn part_one(input: &str) -> Option<u64> {
+    let network = parse_input(input)?;
+    let start = network.graph.id("AAA")?;
+    let end = network.graph.id("ZZZ")?;
 
-    Some(steps)
+    first_common_hit(&[network.hits(start, |node| node == end)])
 }
 
-pub fn part_two(_input: &str) -> Option<u32> {
-    let (directions, mut graph, nodes) = parse_input(_input);
-    let mut cycle_lengths = Vec::new();
-
-    for (name, node) in &nodes {
-        if name.ends_with('A') {
-            graph.set_root(node.clone());
-            let mut current_node = graph.root.clone();
-            let mut directions_index = 0;
-            let mut steps = 0;
-
-            while let Some(node_rc) = &current_node {
-                let next_node;
-                let is_end_with_z;
-
-                {
-                    let node = node_rc.borrow();
-                    // Check if the node ends with 'Z'
-                    is_end_with_z = node.value.ends_with('Z');
-
-                    next_node = if directions[directions_index] == 'L' {
-                        node.left.clone()
-                    } else {
-                        node.right.clone()
-                    };
-                } // The borrow ends here
-
-                if is_end_with_z {
-                    cycle_lengths.push(steps);
-                    break;
-                }
-
-                steps += 1;
-                current_node = next_node.or_else(|| graph.root.clone());
-                directions_index = (directions_index + 1) % directions.len();
-            }
-        }
-    }
-
-    println!("Cycle Lengths: {:?}", cycle_lengths);
-    if cycle_lengths.is_empty() {
-        None
-    } else {
-        let mut result_lcm = cycle_lengths[0];
-        for &length in &cycle_lengths[1..] {
-            result_lcm = lcm(result_lcm, length);
-        }
-
-        println!("LCM: {}", result_lcm);
-        Some(result_lcm as u32)
-    }
+pub fn part_two(input: &str) -> Option<u64> {
+    let network = parse_input(input)?;
+    let graph = &network.graph;
+
+    // Precompute which nodes are ends, so the walk does not have to look at names.
+    let is_end: Vec<bool> = graph
+        .nodes()
+        .map(|node| graph.name(node).ends_with('Z'))
+        .collect();
+
+    // Every ghost walks into a cycle eventually. Where the cycles line up is solved with the CRT,
+    // which also covers ghosts that pass end nodes before their cycle starts or several times per cycle.
+    let hits: Vec<Hits> = graph
+        .nodes()
+        .filter(|&node| graph.name(node).ends_with('A'))
+        .map(|start| network.hits(start, |node| is_end[node.index()]))
+        .collect();
+
+    first_common_hit(&hits)
 }
 
 #[cfg(test)]
@@ -192,12 +94,14 @@ mod tests {
     #[test]
     fn test_part_one() {
         let result = part_one(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, None);
+        assert_eq!(result, Some(6));
     }
 
     #[test]
     fn test_part_two() {
-        let result = part_two(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, None);
+        let result = part_two(&advent_of_code::template::read_file_part(
+            "examples", DAY, 2,
+        ));
+        assert_eq!(result, Some(6));
     }
 }
@@ -0,0 +1,316 @@
+/// Module that detects cycles in sequences of states, to fast-forward them or to find when they
+/// line up.
+use crate::math::crt;
+
+/// The shape of an eventually periodic sequence `s₀, s₁ = step(s₀), …`: after `tail` steps, the states
+/// repeat every `length` steps, i.e. `s[tail + length] == s[tail]`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cycle {
+    pub tail: u64,
+    pub length: u64,
+}
+
+/// Detects the cycle of the sequence starting at `start` with Brent's algorithm.
+/// Only keeps two states in memory. Does not terminate if the sequence never repeats.
+///
+/// ```
+/// # use advent_of_code::cycles::{brent, Cycle};
+/// // 0, 1, 2, 3, 4, 2, 3, 4, …
+/// let cycle = brent(0, |&x| if x == 4 { 2 } else { x + 1 });
+/// assert_eq!(cycle, Cycle { tail: 2, length: 3 });
+/// ```
+pub fn brent<S: Eq + Clone>(start: S, step: impl Fn(&S) -> S) -> Cycle {
+    // Find the length by letting the hare run ahead in powers of two.
+    let mut power = 1;
+    let mut length = 1;
+    let mut tortoise = start.clone();
+    let mut hare = step(&start);
+
+    while tortoise != hare {
+        if power == length {
+            tortoise = hare.clone();
+            power *= 2;
+            length = 0;
+        }
+        hare = step(&hare);
+        length += 1;
+    }
+
+    // Then find the tail with the hare `length` steps ahead.
+    let mut tortoise = start.clone();
+    let mut hare = start;
+    for _ in 0..length {
+        hare = step(&hare);
+    }
+
+    let mut tail = 0;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        tail += 1;
+    }
+
+    Cycle { tail, length }
+}
+
+/// Detects the cycle of the sequence starting at `start` with Floyd's algorithm, see [`brent`].
+pub fn floyd<S: Eq + Clone>(start: S, step: impl Fn(&S) -> S) -> Cycle {
+    let mut tortoise = step(&start);
+    let mut hare = step(&tortoise);
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&step(&hare));
+    }
+
+    let mut tail = 0;
+    tortoise = start;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        tail += 1;
+    }
+
+    let mut length = 1;
+    hare = step(&tortoise);
+    while tortoise != hare {
+        hare = step(&hare);
+        length += 1;
+    }
+
+    Cycle { tail, length }
+}
+
+impl Cycle {
+    /// Returns the smallest step that has the same state as step `n`.
+    #[must_use]
+    pub fn reduce(&self, n: u64) -> u64 {
+        if n < self.tail {
+            n
+        } else {
+            self.tail + (n - self.tail) % self.length
+        }
+    }
+
+    /// Walks the sequence once through its tail and cycle and records the steps whose state matches `is_hit`.
+    pub fn hits<S>(&self, start: S, step: impl Fn(&S) -> S, is_hit: impl Fn(&S) -> bool) -> Hits {
+        let mut hits = Hits {
+            tail: vec![],
+            cycle: vec![],
+            cycle_start: self.tail,
+            cycle_length: self.length,
+        };
+
+        let mut state = start;
+        for n in 0..self.tail + self.length {
+            if is_hit(&state) {
+                if n < self.tail {
+                    hits.tail.push(n);
+                } else {
+                    hits.cycle.push(n);
+                }
+            }
+            state = step(&state);
+        }
+
+        hits
+    }
+}
+
+/// Returns the state after `n` steps without walking all of them, by skipping whole cycles.
+///
+/// ```
+/// # use advent_of_code::cycles::fast_forward;
+/// let state = fast_forward(0u64, |&x| (x * x + 1) % 1000, 1_000_000_000_000);
+/// assert!(state < 1000);
+/// ```
+pub fn fast_forward<S: Eq + Clone>(start: S, step: impl Fn(&S) -> S, n: u64) -> S {
+    let cycle = brent(start.clone(), &step);
+    let mut state = start;
+    for _ in 0..cycle.reduce(n) {
+        state = step(&state);
+    }
+    state
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// The steps at which an eventually periodic sequence is in a state of interest, see [`Cycle::hits`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Hits {
+    /// Hits before the cycle starts, in ascending order.
+    pub tail: Vec<u64>,
+    /// Hits during the first pass through the cycle, in ascending order. Every one of them
+    /// repeats every `cycle_length` steps.
+    pub cycle: Vec<u64>,
+    pub cycle_start: u64,
+    pub cycle_length: u64,
+}
+
+impl Hits {
+    /// Returns whether the sequence is in a state of interest after `n` steps.
+    #[must_use]
+    pub fn contains(&self, n: u64) -> bool {
+        if n < self.cycle_start {
+            self.tail.binary_search(&n).is_ok()
+        } else {
+            let n = self.cycle_start + (n - self.cycle_start) % self.cycle_length;
+            self.cycle.binary_search(&n).is_ok()
+        }
+    }
+
+    /// Iterates all hits in ascending order.
+    pub fn iter(&self) -> impl Iterator<Item = u64> + '_ {
+        // Without hits in the cycle, there are no repeats to iterate endlessly.
+        let laps = if self.cycle.is_empty() {
+            0..0
+        } else {
+            0..u64::MAX
+        };
+        let repeats =
+            laps.flat_map(move |k| self.cycle.iter().map(move |n| n + k * self.cycle_length));
+        self.tail.iter().copied().chain(repeats)
+    }
+}
+
+/// Returns the first step at which all sequences are in a state of interest at the same time,
+/// or [`None`] if that never happens.
+///
+/// Steps before every cycle started are checked one by one, later steps are solved with the
+/// Chinese remainder theorem for every combination of hits within the cycles.
+#[must_use]
+pub fn first_common_hit(hits: &[Hits]) -> Option<u64> {
+    let first = hits.first()?;
+    let threshold = hits.iter().map(|h| h.cycle_start).max()?;
+
+    if let Some(n) = first
+        .iter()
+        .take_while(|&n| n < threshold)
+        .find(|&n| hits.iter().all(|h| h.contains(n)))
+    {
+        return Some(n);
+    }
+
+    // Every combination of one hit per cycle, as (residue, modulus) congruences.
+    let mut combinations: Vec<Vec<(i128, i128)>> = vec![vec![]];
+    for h in hits {
+        combinations = combinations
+            .into_iter()
+            .flat_map(|congruences| {
+                h.cycle.iter().map(move |&n| {
+                    let mut congruences = congruences.clone();
+                    congruences.push((i128::from(n), i128::from(h.cycle_length)));
+                    congruences
+                })
+            })
+            .collect();
+    }
+
+    combinations
+        .into_iter()
+        .filter_map(|congruences| {
+            let (x, m) = crt(congruences)?;
+            // The smallest solution that is not before the threshold.
+            let threshold = i128::from(threshold);
+            let n = if x < threshold {
+                x + (threshold - x + m - 1) / m * m
+            } else {
+                x
+            };
+            u64::try_from(n).ok()
+        })
+        .min()
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{brent, fast_forward, first_common_hit, floyd, Cycle, Hits};
+
+    /// 0, 1, …, 9, 10, …, 16, 10, …
+    fn step(x: &u64) -> u64 {
+        if *x == 16 {
+            10
+        } else {
+            x + 1
+        }
+    }
+
+    #[test]
+    fn detects_cycles() {
+        let expected = Cycle {
+            tail: 10,
+            length: 7,
+        };
+        assert_eq!(brent(0, step), expected);
+        assert_eq!(floyd(0, step), expected);
+        assert_eq!(brent(12, step), Cycle { tail: 0, length: 7 });
+        assert_eq!(floyd(5, |&x| x), Cycle { tail: 0, length: 1 });
+    }
+
+    #[test]
+    fn fast_forwards() {
+        let cycle = brent(0, step);
+        assert_eq!(cycle.reduce(3), 3);
+        assert_eq!(cycle.reduce(17), 10);
+        assert_eq!(fast_forward(0, step, 1_000_000), 10 + (1_000_000 - 10) % 7);
+    }
+
+    #[test]
+    fn records_hits() {
+        let cycle = brent(0, step);
+        let hits = cycle.hits(0, step, |x| x % 5 == 0);
+        assert_eq!(
+            hits,
+            Hits {
+                tail: vec![0, 5],
+                cycle: vec![10, 15],
+                cycle_start: 10,
+                cycle_length: 7,
+            }
+        );
+        assert!(hits.contains(17));
+        assert!(hits.contains(22));
+        assert!(!hits.contains(23));
+        assert_eq!(
+            hits.iter().take(6).collect::<Vec<_>>(),
+            [0, 5, 10, 15, 17, 22]
+        );
+    }
+
+    fn hits(tail: &[u64], cycle: &[u64], cycle_start: u64, cycle_length: u64) -> Hits {
+        Hits {
+            tail: tail.to_vec(),
+            cycle: cycle.to_vec(),
+            cycle_start,
+            cycle_length,
+        }
+    }
+
+    #[test]
+    fn finds_first_common_hit() {
+        // Clean cycles with hits at their end are the LCM case.
+        let a = hits(&[], &[2], 1, 2);
+        let b = hits(&[], &[3], 1, 3);
+        assert_eq!(first_common_hit(&[a, b]), Some(6));
+
+        // A hit in the tail wins if every other sequence also hits it.
+        let c = hits(&[1], &[5], 4, 3);
+        assert_eq!(
+            first_common_hit(&[c.clone(), hits(&[], &[1], 0, 5)]),
+            Some(1)
+        );
+
+        // Offset cycles need the CRT: n ≡ 1 (mod 4), n ≡ 2 (mod 3), n >= 4.
+        let d = hits(&[], &[5], 4, 4);
+        assert_eq!(first_common_hit(&[d, c]), Some(5));
+
+        // Never in sync.
+        let even = hits(&[], &[0], 0, 2);
+        let odd = hits(&[], &[1], 0, 2);
+        assert_eq!(first_common_hit(&[even, odd]), None);
+        assert_eq!(first_common_hit(&[]), None);
+        assert_eq!(first_common_hit(&[hits(&[1], &[], 5, 2)]), Some(1));
+        assert_eq!(first_common_hit(&[hits(&[], &[], 5, 2)]), None);
+    }
+}
@@ -0,0 +1,378 @@
+/// Module that generates random puzzle inputs for stress and property testing: a seeded [`Rng`],
+/// input [`Size`]s, and a [`Property`] harness that runs parts against many generated inputs.
+use std::error::Error;
+use std::fmt::{Debug, Display};
+use std::ops::Range;
+use std::panic::{self, AssertUnwindSafe};
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+/// A generator registered with [`solution!`](crate::solution!), e.g. `solution!(7, generate = generate)`.
+pub type Generator = fn(u64, Size) -> String;
+
+/// A small, fast and reproducible random number generator (SplitMix64).
+/// Not suitable for cryptography.
+///
+/// ```
+/// # use advent_of_code::generate::Rng;
+/// let mut rng = Rng::new(42);
+/// let roll = rng.range(1..7);
+/// assert!((1..7).contains(&roll));
+/// assert_eq!(Rng::new(42).range(1..7), roll);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    #[must_use]
+    pub fn new(seed: u64) -> Self {
+        Rng { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Returns a number in `range`, which must not be empty.
+    /// Uses a multiply-shift reduction, whose bias is negligible for puzzle-sized ranges.
+    pub fn range(&mut self, range: Range<u64>) -> u64 {
+        assert!(!range.is_empty(), "can not pick from an empty range");
+        let span = range.end - range.start;
+        #[allow(clippy::cast_possible_truncation)]
+        let offset = ((u128::from(self.next_u64()) * u128::from(span)) >> 64) as u64;
+        range.start + offset
+    }
+
+    /// Returns an index into a collection of length `len`, which must not be 0.
+    pub fn index(&mut self, len: usize) -> usize {
+        usize::try_from(self.range(0..len as u64)).expect("index fits into usize")
+    }
+
+    /// Returns `true` with the given probability.
+    pub fn chance(&mut self, probability: f64) -> bool {
+        self.f64() < probability
+    }
+
+    /// Returns a number in `0.0..1.0`.
+    pub fn f64(&mut self) -> f64 {
+        #[allow(clippy::cast_precision_loss)]
+        let value = (self.next_u64() >> 11) as f64;
+        value / (1u64 << 53) as f64
+    }
+
+    /// Returns a random element of `items`, which must not be empty.
+    pub fn choose<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.index(items.len())]
+    }
+
+    /// Shuffles `items` in place (Fisher-Yates).
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.index(i + 1));
+        }
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// How big a generated input should be. Generators decide what that means for their puzzle.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Size {
+    #[default]
+    Small,
+    Medium,
+    Large,
+}
+
+impl Size {
+    pub const ALL: [Size; 3] = [Size::Small, Size::Medium, Size::Large];
+
+    /// Picks the value for this size.
+    ///
+    /// ```
+    /// # use advent_of_code::generate::Size;
+    /// assert_eq!(Size::Medium.pick(10, 100, 1000), 100);
+    /// ```
+    pub fn pick<T>(self, small: T, medium: T, large: T) -> T {
+        match self {
+            Size::Small => small,
+            Size::Medium => medium,
+            Size::Large => large,
+        }
+    }
+}
+
+impl FromStr for Size {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "small" => Ok(Size::Small),
+            "medium" => Ok(Size::Medium),
+            "large" => Ok(Size::Large),
+            _ => Err(format!(
+                "unknown size \"{s}\", expecting small, medium or large."
+            )),
+        }
+    }
+}
+
+impl Display for Size {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Size::Small => "small",
+            Size::Medium => "medium",
+            Size::Large => "large",
+        })
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// Runs implementations of a part against generated inputs and checks that none of them panics,
+/// returns no answer or exceeds the time budget, and that they all agree.
+///
+/// ```
+/// # use advent_of_code::generate::{Property, Rng, Size};
+/// fn generate(seed: u64, size: Size) -> String {
+///     let mut rng = Rng::new(seed);
+///     let count = size.pick(10, 100, 1000);
+///     (0..count).map(|_| format!("{}\n", rng.range(0..100))).collect()
+/// }
+/// fn sum(input: &str) -> Option<u64> {
+///     input.lines().map(|l| l.parse::<u64>().ok()).sum()
+/// }
+/// fn sum_naive(input: &str) -> Option<u64> {
+///     input.lines().try_fold(0, |acc, l| Some(acc + l.parse::<u64>().ok()?))
+/// }
+///
+/// Property::new().run(generate, &[("sum", sum), ("naive", sum_naive)]).unwrap();
+/// ```
+#[derive(Debug, Clone)]
+pub struct Property {
+    seeds: Range<u64>,
+    sizes: Vec<Size>,
+    budget: Duration,
+}
+
+impl Default for Property {
+    fn default() -> Self {
+        Property {
+            seeds: 0..32,
+            sizes: vec![Size::Small, Size::Medium],
+            budget: Duration::from_secs(1),
+        }
+    }
+}
+
+/// An implementation of a part, named for failure reports.
+pub type Implementation<'a, T> = (&'a str, fn(&str) -> Option<T>);
+
+impl Property {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Generates one input per seed and size.
+    #[must_use]
+    pub fn seeds(self, seeds: Range<u64>) -> Self {
+        Property { seeds, ..self }
+    }
+
+    #[must_use]
+    pub fn sizes(self, sizes: &[Size]) -> Self {
+        Property {
+            sizes: sizes.to_vec(),
+            ..self
+        }
+    }
+
+    /// The longest a single implementation may take for one input.
+    #[must_use]
+    pub fn budget(self, budget: Duration) -> Self {
+        Property { budget, ..self }
+    }
+
+    /// Checks every implementation against every generated input, stopping at the first failure.
+    pub fn run<T: PartialEq + Debug>(
+        &self,
+        generate: Generator,
+        implementations: &[Implementation<T>],
+    ) -> Result<(), Failure> {
+        for &size in &self.sizes {
+            for seed in self.seeds.clone() {
+                let input = generate(seed, size);
+                let fail = |reason| Failure {
+                    seed,
+                    size,
+                    input: input.clone(),
+                    reason,
+                };
+
+                let mut answers = vec![];
+                for &(name, part) in implementations {
+                    let timer = Instant::now();
+                    let answer = panic::catch_unwind(AssertUnwindSafe(|| part(&input))).map_err(
+                        |payload| fail(Reason::Panic(name.into(), panic_message(&*payload))),
+                    )?;
+                    let elapsed = timer.elapsed();
+
+                    if elapsed > self.budget {
+                        return Err(fail(Reason::Timeout(name.into(), elapsed)));
+                    }
+                    let Some(answer) = answer else {
+                        return Err(fail(Reason::NoAnswer(name.into())));
+                    };
+                    answers.push((name, answer));
+                }
+
+                if answers.windows(2).any(|pair| pair[0].1 != pair[1].1) {
+                    let answers = answers
+                        .iter()
+                        .map(|(name, answer)| format!("{name} = {answer:?}"))
+                        .collect();
+                    return Err(fail(Reason::Disagreement(answers)));
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn panic_message(payload: &(dyn std::any::Any + Send)) -> String {
+    payload
+        .downcast_ref::<&str>()
+        .map(ToString::to_string)
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "unknown panic".into())
+}
+
+/// The first generated input a [`Property`] did not hold for.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Failure {
+    pub seed: u64,
+    pub size: Size,
+    pub input: String,
+    pub reason: Reason,
+}
+
+/// Why a [`Property`] did not hold, naming the implementation(s) at fault.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Reason {
+    Panic(String, String),
+    Timeout(String, Duration),
+    NoAnswer(String),
+    /// The answers of all implementations, formatted as `name = answer`.
+    Disagreement(Vec<String>),
+}
+
+impl Error for Failure {}
+
+impl Display for Failure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.reason {
+            Reason::Panic(name, message) => write!(f, "{name} panicked: {message}")?,
+            Reason::Timeout(name, elapsed) => write!(f, "{name} took {elapsed:.1?}")?,
+            Reason::NoAnswer(name) => write!(f, "{name} returned no answer")?,
+            Reason::Disagreement(answers) => {
+                write!(f, "implementations disagree: {}", answers.join(", "))?;
+            }
+        }
+        write!(
+            f,
+            " for the input generated with --seed {} --size {}",
+            self.seed, self.size
+        )
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{Failure, Property, Reason, Rng, Size};
+    use std::time::Duration;
+
+    #[test]
+    fn generates_reproducibly() {
+        // Reference values of SplitMix64 for seed 0.
+        let mut rng = Rng::new(0);
+        assert_eq!(rng.next_u64(), 0xe220_a839_7b1d_cdaf);
+        assert_eq!(rng.next_u64(), 0x6e78_9e6a_a1b9_65f4);
+
+        let mut rng = Rng::new(7);
+        let values: Vec<u64> = (0..1000).map(|_| rng.range(10..13)).collect();
+        assert!(values.iter().all(|v| (10..13).contains(v)));
+        assert!((10..13).all(|v| values.contains(&v)));
+        assert!((0..1000).all(|_| rng.f64() < 1.0));
+
+        let mut items: Vec<u32> = (0..20).collect();
+        rng.shuffle(&mut items);
+        assert_ne!(items, (0..20).collect::<Vec<_>>());
+        items.sort_unstable();
+        assert_eq!(items, (0..20).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn parses_sizes() {
+        assert_eq!("large".parse(), Ok(Size::Large));
+        assert!("huge".parse::<Size>().is_err());
+        assert_eq!(Size::Small.to_string(), "small");
+    }
+
+    fn generate(seed: u64, size: Size) -> String {
+        let mut rng = Rng::new(seed);
+        (0..size.pick(3, 30, 300))
+            .map(|_| format!("{}\n", rng.range(0..10)))
+            .collect()
+    }
+
+    fn sum(input: &str) -> Option<u32> {
+        input.lines().map(|l| l.parse::<u32>().ok()).sum()
+    }
+
+    fn sum_wrong(input: &str) -> Option<u32> {
+        sum(input).map(|s| if s > 20 { s + 1 } else { s })
+    }
+
+    fn explode(input: &str) -> Option<u32> {
+        assert!(input.len() < 20, "input too long");
+        Some(0)
+    }
+
+    #[test]
+    fn checks_properties() {
+        assert_eq!(Property::new().run(generate, &[("sum", sum)]), Ok(()));
+
+        let failure = Property::new()
+            .sizes(&[Size::Medium])
+            .run(generate, &[("sum", sum), ("wrong", sum_wrong)])
+            .unwrap_err();
+        assert_eq!(failure.size, Size::Medium);
+        assert!(matches!(failure.reason, Reason::Disagreement(_)));
+
+        let failure: Failure = Property::new()
+            .sizes(&[Size::Large])
+            .run(generate, &[("explode", explode)])
+            .unwrap_err();
+        assert_eq!(
+            failure.to_string(),
+            "explode panicked: input too long for the input generated with --seed 0 --size large"
+        );
+
+        let failure = Property::new()
+            .seeds(0..1)
+            .budget(Duration::ZERO)
+            .run(generate, &[("sum", sum)])
+            .unwrap_err();
+        assert!(matches!(failure.reason, Reason::Timeout(..)));
+    }
+}
@@ -0,0 +1,537 @@
+/// Module that stores graphs in an arena of dense node ids, with builders, traversals and shortest
+/// paths.
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+use std::ops::Add;
+
+/// The id of a node in a [`Graph`]. Ids are dense, starting at zero in the order nodes were added.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct NodeId(u32);
+
+impl NodeId {
+    /// Returns the id as an index into per-node storage, e.g. a `Vec` of length [`Graph::len`].
+    #[must_use]
+    pub fn index(self) -> usize {
+        self.0 as usize
+    }
+}
+
+/// Maps node names to dense [`NodeId`]s, storing every name once.
+#[derive(Debug, Clone, Default)]
+pub struct Interner {
+    ids: HashMap<String, NodeId>,
+    names: Vec<String>,
+}
+
+impl Interner {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the id of `name`, assigning the next free id if it has not been seen yet.
+    pub fn intern(&mut self, name: &str) -> NodeId {
+        if let Some(&id) = self.ids.get(name) {
+            return id;
+        }
+        let id = NodeId(u32::try_from(self.names.len()).expect("too many nodes"));
+        self.ids.insert(name.to_string(), id);
+        self.names.push(name.to_string());
+        id
+    }
+
+    #[must_use]
+    pub fn get(&self, name: &str) -> Option<NodeId> {
+        self.ids.get(name).copied()
+    }
+
+    #[must_use]
+    pub fn name(&self, id: NodeId) -> &str {
+        &self.names[id.index()]
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// Collects nodes and edges before they are packed into a [`Graph`].
+///
+/// ```
+/// # use advent_of_code::graph::GraphBuilder;
+/// let mut builder = GraphBuilder::new();
+/// builder.add_edge("AAA", "BBB", 1);
+/// builder.add_edge("AAA", "CCC", 1);
+/// let graph = builder.build();
+/// let aaa = graph.id("AAA").unwrap();
+/// assert_eq!(graph.neighbours(aaa).len(), 2);
+/// ```
+#[derive(Debug, Clone)]
+pub struct GraphBuilder<W = ()> {
+    nodes: Interner,
+    edges: Vec<(NodeId, NodeId, W)>,
+}
+
+impl<W> Default for GraphBuilder<W> {
+    fn default() -> Self {
+        Self {
+            nodes: Interner::new(),
+            edges: vec![],
+        }
+    }
+}
+
+impl<W> GraphBuilder<W> {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a node without edges, returning its id. Adding a node twice returns the same id.
+    pub fn add_node(&mut self, name: &str) -> NodeId {
+        self.nodes.intern(name)
+    }
+
+    /// Adds a directed edge, creating both nodes if needed.
+    /// The edges of a node keep the order in which they were added.
+    pub fn add_edge(&mut self, from: &str, to: &str, weight: W) {
+        let from = self.nodes.intern(from);
+        let to = self.nodes.intern(to);
+        self.edges.push((from, to, weight));
+    }
+
+    /// Adds an edge in both directions.
+    pub fn add_undirected_edge(&mut self, a: &str, b: &str, weight: W)
+    where
+        W: Clone,
+    {
+        self.add_edge(a, b, weight.clone());
+        self.add_edge(b, a, weight);
+    }
+
+    /// Packs the edges into compact adjacency storage.
+    #[must_use]
+    pub fn build(mut self) -> Graph<W> {
+        // A stable sort keeps the insertion order of the edges of every node.
+        self.edges.sort_by_key(|&(from, _, _)| from);
+
+        let mut offsets = vec![0; self.nodes.len() + 1];
+        for &(from, _, _) in &self.edges {
+            offsets[from.index() + 1] += 1;
+        }
+        for i in 1..offsets.len() {
+            offsets[i] += offsets[i - 1];
+        }
+
+        let (targets, weights) = self.edges.into_iter().map(|(_, to, w)| (to, w)).unzip();
+
+        Graph {
+            nodes: self.nodes,
+            offsets,
+            targets,
+            weights,
+        }
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// A directed graph with named nodes. The edges of all nodes are stored back to back in a single
+/// `Vec`, so looking up the neighbours of a node is a slice access.
+#[derive(Debug, Clone)]
+pub struct Graph<W = ()> {
+    nodes: Interner,
+    offsets: Vec<usize>,
+    targets: Vec<NodeId>,
+    weights: Vec<W>,
+}
+
+impl<W> Graph<W> {
+    /// Returns the number of nodes.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    #[must_use]
+    pub fn id(&self, name: &str) -> Option<NodeId> {
+        self.nodes.get(name)
+    }
+
+    #[must_use]
+    pub fn name(&self, id: NodeId) -> &str {
+        self.nodes.name(id)
+    }
+
+    /// Iterates all node ids in the order the nodes were added.
+    pub fn nodes(&self) -> impl Iterator<Item = NodeId> {
+        (0..self.nodes.len()).map(|i| NodeId(i as u32))
+    }
+
+    /// Returns the targets of the outgoing edges of `id`, in the order the edges were added.
+    #[must_use]
+    pub fn neighbours(&self, id: NodeId) -> &[NodeId] {
+        &self.targets[self.offsets[id.index()]..self.offsets[id.index() + 1]]
+    }
+
+    /// Iterates the outgoing edges of `id` together with their weights.
+    pub fn edges(&self, id: NodeId) -> impl Iterator<Item = (NodeId, &W)> + '_ {
+        let range = self.offsets[id.index()]..self.offsets[id.index() + 1];
+        self.targets[range.clone()]
+            .iter()
+            .copied()
+            .zip(&self.weights[range])
+    }
+
+    /// Iterates the nodes reachable from `start` in breadth-first order, together with their distance in edges.
+    pub fn bfs(&self, start: NodeId) -> impl Iterator<Item = (NodeId, usize)> + '_ {
+        let mut seen = vec![false; self.len()];
+        seen[start.index()] = true;
+        let mut queue = VecDeque::from([(start, 0)]);
+
+        std::iter::from_fn(move || {
+            let (node, depth) = queue.pop_front()?;
+            for &next in self.neighbours(node) {
+                if !seen[next.index()] {
+                    seen[next.index()] = true;
+                    queue.push_back((next, depth + 1));
+                }
+            }
+            Some((node, depth))
+        })
+    }
+
+    /// Iterates the nodes reachable from `start` in depth-first pre-order.
+    pub fn dfs(&self, start: NodeId) -> impl Iterator<Item = NodeId> + '_ {
+        let mut seen = vec![false; self.len()];
+        let mut stack = vec![start];
+
+        std::iter::from_fn(move || loop {
+            let node = stack.pop()?;
+            if seen[node.index()] {
+                continue;
+            }
+            seen[node.index()] = true;
+            // Push in reverse, so the first neighbour is visited first.
+            stack.extend(
+                self.neighbours(node)
+                    .iter()
+                    .rev()
+                    .filter(|next| !seen[next.index()]),
+            );
+            return Some(node);
+        })
+    }
+
+    /// Returns all nodes so that every edge points from an earlier to a later node,
+    /// or [`None`] if the graph has a cycle.
+    #[must_use]
+    pub fn topological_sort(&self) -> Option<Vec<NodeId>> {
+        let mut in_degree = vec![0usize; self.len()];
+        for target in &self.targets {
+            in_degree[target.index()] += 1;
+        }
+
+        let mut queue: VecDeque<NodeId> = self
+            .nodes()
+            .filter(|id| in_degree[id.index()] == 0)
+            .collect();
+        let mut order = Vec::with_capacity(self.len());
+
+        while let Some(node) = queue.pop_front() {
+            order.push(node);
+            for &next in self.neighbours(node) {
+                in_degree[next.index()] -= 1;
+                if in_degree[next.index()] == 0 {
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        (order.len() == self.len()).then_some(order)
+    }
+
+    /// Groups nodes that are connected when edge directions are ignored.
+    /// Components are ordered by their first node, and nodes within a component by id.
+    #[must_use]
+    pub fn connected_components(&self) -> Vec<Vec<NodeId>> {
+        let mut sets = UnionFind::new(self.len());
+        for node in self.nodes() {
+            for &next in self.neighbours(node) {
+                sets.union(node.index(), next.index());
+            }
+        }
+
+        let mut component_of_root = HashMap::new();
+        let mut components: Vec<Vec<NodeId>> = vec![];
+        for node in self.nodes() {
+            let root = sets.find(node.index());
+            let component = *component_of_root.entry(root).or_insert_with(|| {
+                components.push(vec![]);
+                components.len() - 1
+            });
+            components[component].push(node);
+        }
+
+        components
+    }
+}
+
+impl<W> Graph<W>
+where
+    W: Copy + Ord + Add<Output = W> + Default,
+{
+    /// Returns the length of the shortest path from `start` to every node, or [`None`] for unreachable nodes.
+    /// Indexed by [`NodeId::index`].
+    #[must_use]
+    pub fn dijkstra(&self, start: NodeId) -> Vec<Option<W>> {
+        let mut dist: Vec<Option<W>> = vec![None; self.len()];
+        let mut heap = BinaryHeap::from([Reverse((W::default(), start))]);
+        dist[start.index()] = Some(W::default());
+
+        while let Some(Reverse((cost, node))) = heap.pop() {
+            if dist[node.index()].is_some_and(|best| cost > best) {
+                continue;
+            }
+            for (next, &weight) in self.edges(node) {
+                let next_cost = cost + weight;
+                if dist[next.index()].is_none_or(|best| next_cost < best) {
+                    dist[next.index()] = Some(next_cost);
+                    heap.push(Reverse((next_cost, next)));
+                }
+            }
+        }
+
+        dist
+    }
+
+    /// Finds a shortest path from `start` to `goal` with A*, returning its length and nodes.
+    /// `heuristic` must never overestimate the remaining distance to `goal`;
+    /// a heuristic that always returns zero makes this a plain Dijkstra search.
+    pub fn astar(
+        &self,
+        start: NodeId,
+        goal: NodeId,
+        mut heuristic: impl FnMut(NodeId) -> W,
+    ) -> Option<(W, Vec<NodeId>)> {
+        let mut dist: Vec<Option<W>> = vec![None; self.len()];
+        let mut previous: Vec<Option<NodeId>> = vec![None; self.len()];
+        let mut heap = BinaryHeap::from([Reverse((heuristic(start), W::default(), start))]);
+        dist[start.index()] = Some(W::default());
+
+        while let Some(Reverse((_, cost, node))) = heap.pop() {
+            if node == goal {
+                let mut path = vec![goal];
+                while let Some(prev) = previous[path[path.len() - 1].index()] {
+                    path.push(prev);
+                }
+                path.reverse();
+                return Some((cost, path));
+            }
+            if dist[node.index()].is_some_and(|best| cost > best) {
+                continue;
+            }
+            for (next, &weight) in self.edges(node) {
+                let next_cost = cost + weight;
+                if dist[next.index()].is_none_or(|best| next_cost < best) {
+                    dist[next.index()] = Some(next_cost);
+                    previous[next.index()] = Some(node);
+                    heap.push(Reverse((next_cost + heuristic(next), next_cost, next)));
+                }
+            }
+        }
+
+        None
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// Disjoint sets over the elements `0..n`, with path compression and union by size.
+#[derive(Debug, Clone)]
+pub struct UnionFind {
+    parent: Vec<usize>,
+    size: Vec<usize>,
+    count: usize,
+}
+
+impl UnionFind {
+    /// Creates `n` singleton sets.
+    #[must_use]
+    pub fn new(n: usize) -> Self {
+        Self {
+            parent: (0..n).collect(),
+            size: vec![1; n],
+            count: n,
+        }
+    }
+
+    /// Returns the representative of the set containing `x`.
+    pub fn find(&mut self, x: usize) -> usize {
+        let mut root = x;
+        while self.parent[root] != root {
+            root = self.parent[root];
+        }
+
+        let mut x = x;
+        while self.parent[x] != root {
+            let next = self.parent[x];
+            self.parent[x] = root;
+            x = next;
+        }
+
+        root
+    }
+
+    /// Merges the sets containing `a` and `b`. Returns `false` if they already were the same set.
+    pub fn union(&mut self, a: usize, b: usize) -> bool {
+        let (mut a, mut b) = (self.find(a), self.find(b));
+        if a == b {
+            return false;
+        }
+        if self.size[a] < self.size[b] {
+            std::mem::swap(&mut a, &mut b);
+        }
+        self.parent[b] = a;
+        self.size[a] += self.size[b];
+        self.count -= 1;
+        true
+    }
+
+    pub fn same(&mut self, a: usize, b: usize) -> bool {
+        self.find(a) == self.find(b)
+    }
+
+    /// Returns the number of elements in the set containing `x`.
+    pub fn set_size(&mut self, x: usize) -> usize {
+        let root = self.find(x);
+        self.size[root]
+    }
+
+    /// Returns the number of disjoint sets.
+    #[must_use]
+    pub fn count(&self) -> usize {
+        self.count
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{Graph, GraphBuilder, NodeId, UnionFind};
+
+    /// a -1-> b -1-> d
+    /// a -4-> c -1-> d
+    /// a -7-> d      e
+    fn get_mock_graph() -> Graph<u32> {
+        let mut builder = GraphBuilder::new();
+        builder.add_edge("a", "b", 1);
+        builder.add_edge("a", "c", 4);
+        builder.add_edge("b", "d", 1);
+        builder.add_edge("c", "d", 1);
+        builder.add_edge("a", "d", 7);
+        builder.add_node("e");
+        builder.build()
+    }
+
+    fn names(graph: &Graph<u32>, ids: impl IntoIterator<Item = NodeId>) -> Vec<&str> {
+        ids.into_iter().map(|id| graph.name(id)).collect()
+    }
+
+    #[test]
+    fn interns_node_names() {
+        let graph = get_mock_graph();
+        assert_eq!(graph.len(), 5);
+        let a = graph.id("a").unwrap();
+        assert_eq!(a.index(), 0);
+        assert_eq!(graph.name(a), "a");
+        assert_eq!(graph.id("z"), None);
+        assert_eq!(
+            names(&graph, graph.neighbours(a).iter().copied()),
+            ["b", "c", "d"]
+        );
+        assert!(graph.neighbours(graph.id("e").unwrap()).is_empty());
+    }
+
+    #[test]
+    fn traverses_breadth_and_depth_first() {
+        let graph = get_mock_graph();
+        let a = graph.id("a").unwrap();
+
+        let bfs: Vec<_> = graph
+            .bfs(a)
+            .map(|(id, depth)| (graph.name(id), depth))
+            .collect();
+        assert_eq!(bfs, [("a", 0), ("b", 1), ("c", 1), ("d", 1)]);
+        assert_eq!(names(&graph, graph.dfs(a)), ["a", "b", "d", "c"]);
+    }
+
+    #[test]
+    fn finds_shortest_paths() {
+        let graph = get_mock_graph();
+        let (a, d) = (graph.id("a").unwrap(), graph.id("d").unwrap());
+
+        let dist = graph.dijkstra(a);
+        assert_eq!(dist, [Some(0), Some(1), Some(4), Some(2), None]);
+
+        let (cost, path) = graph.astar(a, d, |_| 0).unwrap();
+        assert_eq!(cost, 2);
+        assert_eq!(names(&graph, path), ["a", "b", "d"]);
+        assert_eq!(graph.astar(d, a, |_| 0), None);
+    }
+
+    #[test]
+    fn sorts_topologically() {
+        let graph = get_mock_graph();
+        let order = names(&graph, graph.topological_sort().unwrap());
+        assert_eq!(order, ["a", "e", "b", "c", "d"]);
+
+        let mut builder = GraphBuilder::new();
+        builder.add_edge("a", "b", ());
+        builder.add_edge("b", "a", ());
+        assert_eq!(builder.build().topological_sort(), None);
+    }
+
+    #[test]
+    fn groups_connected_components() {
+        let mut builder = GraphBuilder::new();
+        builder.add_undirected_edge("a", "b", ());
+        builder.add_edge("c", "d", ());
+        builder.add_edge("e", "b", ());
+        let graph = builder.build();
+
+        let components: Vec<Vec<&str>> = graph
+            .connected_components()
+            .into_iter()
+            .map(|c| c.into_iter().map(|id| graph.name(id)).collect())
+            .collect();
+        assert_eq!(components, [vec!["a", "b", "e"], vec!["c", "d"]]);
+    }
+
+    #[test]
+    fn merges_disjoint_sets() {
+        let mut sets = UnionFind::new(5);
+        assert!(sets.union(0, 1));
+        assert!(sets.union(3, 4));
+        assert!(!sets.union(1, 0));
+        assert!(sets.union(1, 4));
+        assert!(sets.same(0, 3));
+        assert!(!sets.same(0, 2));
+        assert_eq!(sets.set_size(4), 4);
+        assert_eq!(sets.count(), 2);
+    }
+}
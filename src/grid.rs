@@ -0,0 +1,447 @@
+/// Module that provides a two-dimensional grid of cells, parsed from puzzle inputs.
+use std::error::Error;
+use std::fmt::Display;
+use std::ops::{Index, IndexMut};
+
+use crate::style::coloured;
+pub use crate::style::Colour;
+
+/// A position in a [`Grid`], as `(x, y)` with the origin in the top-left corner.
+pub type Pos = (usize, usize);
+
+/// Offsets of the four orthogonal neighbours of a cell.
+const NEIGHBOURS_4: [(isize, isize); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+
+/// Offsets of the eight orthogonal and diagonal neighbours of a cell.
+const NEIGHBOURS_8: [(isize, isize); 8] = [
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+    (0, 1),
+    (-1, 1),
+    (-1, 0),
+];
+
+/// A two-dimensional grid of cells, stored row by row in a single [`Vec`].
+///
+/// ```
+/// # use advent_of_code::grid::Grid;
+/// let grid = Grid::parse("#.\n.#", |c| c == '#').unwrap();
+/// assert_eq!(grid.width(), 2);
+/// assert_eq!(grid[(1, 1)], true);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    /// Creates a grid from cells stored row by row.
+    /// Returns [`None`] if the number of cells does not match the dimensions.
+    #[must_use]
+    pub fn from_vec(width: usize, height: usize, cells: Vec<T>) -> Option<Self> {
+        (width * height == cells.len()).then_some(Self {
+            width,
+            height,
+            cells,
+        })
+    }
+
+    /// Parses a grid from lines of text, mapping every character to a cell with `f`.
+    /// Returns an error if the lines differ in length.
+    pub fn parse(input: &str, mut f: impl FnMut(char) -> T) -> Result<Self, GridParseError> {
+        let mut width = None;
+        let mut height = 0;
+        let mut cells = vec![];
+
+        for line in input.lines() {
+            let len_before = cells.len();
+            cells.extend(line.chars().map(&mut f));
+            let line_width = cells.len() - len_before;
+
+            match width {
+                None => width = Some(line_width),
+                Some(width) if width != line_width => {
+                    return Err(GridParseError {
+                        line: height + 1,
+                        expected: width,
+                        found: line_width,
+                    });
+                }
+                Some(_) => {}
+            }
+
+            height += 1;
+        }
+
+        Ok(Self {
+            width: width.unwrap_or(0),
+            height,
+            cells,
+        })
+    }
+
+    #[must_use]
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    #[must_use]
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    #[must_use]
+    pub fn contains(&self, (x, y): Pos) -> bool {
+        x < self.width && y < self.height
+    }
+
+    #[must_use]
+    pub fn get(&self, pos: Pos) -> Option<&T> {
+        self.contains(pos)
+            .then(|| &self.cells[pos.1 * self.width + pos.0])
+    }
+
+    #[must_use]
+    pub fn get_mut(&mut self, pos: Pos) -> Option<&mut T> {
+        if self.contains(pos) {
+            Some(&mut self.cells[pos.1 * self.width + pos.0])
+        } else {
+            None
+        }
+    }
+
+    /// Returns the position `(dx, dy)` away from `pos`, if it lies inside the grid.
+    #[must_use]
+    pub fn offset(&self, (x, y): Pos, (dx, dy): (isize, isize)) -> Option<Pos> {
+        let pos = (x.checked_add_signed(dx)?, y.checked_add_signed(dy)?);
+        self.contains(pos).then_some(pos)
+    }
+
+    /// Iterates the positions of the (up to) four orthogonal neighbours of `pos`.
+    pub fn neighbours_4(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        NEIGHBOURS_4
+            .iter()
+            .filter_map(move |&delta| self.offset(pos, delta))
+    }
+
+    /// Iterates the positions of the (up to) eight orthogonal and diagonal neighbours of `pos`.
+    pub fn neighbours_8(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        NEIGHBOURS_8
+            .iter()
+            .filter_map(move |&delta| self.offset(pos, delta))
+    }
+
+    /// Iterates all cells together with their positions, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = (Pos, &T)> + '_ {
+        let width = self.width;
+        self.cells
+            .iter()
+            .enumerate()
+            .map(move |(i, cell)| ((i % width, i / width), cell))
+    }
+
+    /// Returns row `y` as a slice.
+    #[must_use]
+    pub fn row(&self, y: usize) -> Option<&[T]> {
+        (y < self.height).then(|| &self.cells[y * self.width..(y + 1) * self.width])
+    }
+
+    /// Iterates the rows of the grid from top to bottom.
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> + '_ {
+        // `chunks` panics for a chunk size of zero, which is what an empty grid has.
+        self.cells.chunks(self.width.max(1))
+    }
+
+    /// Iterates the cells of column `x` from top to bottom.
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> + '_ {
+        let cells = if x < self.width {
+            &self.cells[x..]
+        } else {
+            &[]
+        };
+        cells.iter().step_by(self.width.max(1))
+    }
+
+    /// Iterates the columns of the grid from left to right.
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T> + '_> + '_ {
+        (0..self.width).map(|x| self.column(x))
+    }
+
+    /// Returns the position of the first cell (row by row) that matches `predicate`.
+    pub fn find(&self, mut predicate: impl FnMut(&T) -> bool) -> Option<Pos> {
+        self.iter()
+            .find(|(_, cell)| predicate(cell))
+            .map(|(pos, _)| pos)
+    }
+
+    /// Iterates the positions of all cells that match `predicate`.
+    pub fn positions<'a>(
+        &'a self,
+        mut predicate: impl FnMut(&T) -> bool + 'a,
+    ) -> impl Iterator<Item = Pos> + 'a {
+        self.iter()
+            .filter(move |(_, cell)| predicate(cell))
+            .map(|(pos, _)| pos)
+    }
+
+    /// Creates a grid of the same size by mapping every cell with `f`.
+    #[must_use]
+    pub fn map<U>(&self, mut f: impl FnMut(Pos, &T) -> U) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.iter().map(|(pos, cell)| f(pos, cell)).collect(),
+        }
+    }
+
+    /// Returns a value that displays the grid, styling every cell with the colour returned by `colour`.
+    pub fn display_with<F>(&self, colour: F) -> ColouredGrid<'_, T, F>
+    where
+        F: Fn(Pos, &T) -> Option<Colour>,
+    {
+        ColouredGrid { grid: self, colour }
+    }
+}
+
+impl<T: PartialEq> Grid<T> {
+    /// Returns the position of the first cell (row by row) that equals `value`.
+    #[must_use]
+    pub fn position(&self, value: &T) -> Option<Pos> {
+        self.find(|cell| cell == value)
+    }
+}
+
+impl<T: Clone> Grid<T> {
+    /// Creates a grid of the given size with every cell set to `value`.
+    #[must_use]
+    pub fn new(width: usize, height: usize, value: T) -> Self {
+        Self {
+            width,
+            height,
+            cells: vec![value; width * height],
+        }
+    }
+
+    /// Returns the grid mirrored along its main diagonal, i.e. rows become columns.
+    #[must_use]
+    pub fn transpose(&self) -> Self {
+        let cells = (0..self.width)
+            .flat_map(|x| self.column(x).cloned())
+            .collect();
+
+        Self {
+            width: self.height,
+            height: self.width,
+            cells,
+        }
+    }
+
+    /// Returns the grid rotated by 90 degrees clockwise.
+    #[must_use]
+    pub fn rotate_cw(&self) -> Self {
+        let cells = (0..self.width)
+            .flat_map(|x| {
+                (0..self.height)
+                    .rev()
+                    .map(move |y| self.cells[y * self.width + x].clone())
+            })
+            .collect();
+
+        Self {
+            width: self.height,
+            height: self.width,
+            cells,
+        }
+    }
+
+    /// Returns the grid rotated by 90 degrees counter-clockwise.
+    #[must_use]
+    pub fn rotate_ccw(&self) -> Self {
+        let cells = (0..self.width)
+            .rev()
+            .flat_map(|x| (0..self.height).map(move |y| self.cells[y * self.width + x].clone()))
+            .collect();
+
+        Self {
+            width: self.height,
+            height: self.width,
+            cells,
+        }
+    }
+}
+
+impl<T> Index<Pos> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, pos: Pos) -> &Self::Output {
+        self.get(pos).unwrap_or_else(|| {
+            panic!(
+                "position {pos:?} is out of bounds for a {}x{} grid",
+                self.width, self.height
+            )
+        })
+    }
+}
+
+impl<T> IndexMut<Pos> for Grid<T> {
+    fn index_mut(&mut self, pos: Pos) -> &mut Self::Output {
+        let (width, height) = (self.width, self.height);
+        self.get_mut(pos).unwrap_or_else(|| {
+            panic!("position {pos:?} is out of bounds for a {width}x{height} grid")
+        })
+    }
+}
+
+impl<T: Display> Display for Grid<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.display_with(|_, _| None).fmt(f)
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// Displays a [`Grid`] with per-cell colours. Created by [`Grid::display_with`].
+pub struct ColouredGrid<'a, T, F> {
+    grid: &'a Grid<T>,
+    colour: F,
+}
+
+impl<T, F> Display for ColouredGrid<'_, T, F>
+where
+    T: Display,
+    F: Fn(Pos, &T) -> Option<Colour>,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (y, row) in self.grid.rows().enumerate() {
+            if y > 0 {
+                writeln!(f)?;
+            }
+            for (x, cell) in row.iter().enumerate() {
+                match (self.colour)((x, y), cell) {
+                    Some(colour) => write!(f, "{}", coloured(cell, colour))?,
+                    None => write!(f, "{cell}")?,
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// An error which can be returned when parsing a [`Grid`] from lines of different length.
+#[derive(Debug, PartialEq, Eq)]
+pub struct GridParseError {
+    /// The 1-based number of the offending line.
+    pub line: usize,
+    pub expected: usize,
+    pub found: usize,
+}
+
+impl Error for GridParseError {}
+
+impl Display for GridParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "line {} has {} cells, expecting {} like the first line",
+            self.line, self.found, self.expected
+        )
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{Colour, Grid, GridParseError};
+
+    fn get_mock_grid() -> Grid<char> {
+        Grid::parse("abc\ndef", |c| c).unwrap()
+    }
+
+    #[test]
+    fn parses_grid() {
+        let grid = get_mock_grid();
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid[(2, 1)], 'f');
+        assert_eq!(grid.get((3, 0)), None);
+        assert_eq!(grid.get((0, 2)), None);
+    }
+
+    #[test]
+    fn rejects_ragged_lines() {
+        assert_eq!(
+            Grid::parse("abc\nde", |c| c),
+            Err(GridParseError {
+                line: 2,
+                expected: 3,
+                found: 2
+            })
+        );
+    }
+
+    #[test]
+    fn parses_empty_grid() {
+        let grid = Grid::parse("", |c| c).unwrap();
+        assert_eq!((grid.width(), grid.height()), (0, 0));
+        assert_eq!(grid.rows().count(), 0);
+        assert_eq!(grid.to_string(), "");
+    }
+
+    #[test]
+    fn iterates_neighbours_within_bounds() {
+        let grid = get_mock_grid();
+        let corner: Vec<_> = grid.neighbours_8((0, 0)).collect();
+        assert_eq!(corner, [(1, 0), (1, 1), (0, 1)]);
+
+        let edge: Vec<_> = grid.neighbours_4((1, 1)).collect();
+        assert_eq!(edge, [(1, 0), (2, 1), (0, 1)]);
+        assert_eq!(grid.neighbours_8((1, 0)).count(), 5);
+    }
+
+    #[test]
+    fn views_rows_and_columns() {
+        let grid = get_mock_grid();
+        assert_eq!(grid.row(1), Some(&['d', 'e', 'f'][..]));
+        assert_eq!(grid.row(2), None);
+        assert_eq!(grid.column(1).collect::<String>(), "be");
+        assert_eq!(grid.column(3).count(), 0);
+        let columns: Vec<String> = grid.columns().map(|c| c.collect()).collect();
+        assert_eq!(columns, ["ad", "be", "cf"]);
+    }
+
+    #[test]
+    fn transposes_and_rotates() {
+        let grid = get_mock_grid();
+        assert_eq!(grid.transpose().to_string(), "ad\nbe\ncf");
+        assert_eq!(grid.rotate_cw().to_string(), "da\neb\nfc");
+        assert_eq!(grid.rotate_ccw().to_string(), "cf\nbe\nad");
+        assert_eq!(grid.rotate_cw().rotate_ccw(), grid);
+        assert_eq!(grid.rotate_cw().rotate_cw().rotate_cw().rotate_cw(), grid);
+    }
+
+    #[test]
+    fn finds_positions() {
+        let grid = Grid::parse("#..\n.#.", |c| c).unwrap();
+        assert_eq!(grid.position(&'#'), Some((0, 0)));
+        assert_eq!(grid.find(|c| *c == 'x'), None);
+        let hashes: Vec<_> = grid.positions(|c| *c == '#').collect();
+        assert_eq!(hashes, [(0, 0), (1, 1)]);
+    }
+
+    #[test]
+    fn displays_coloured_cells() {
+        crate::style::set_color_choice(crate::style::ColorChoice::Always);
+        let grid = get_mock_grid();
+        let s = grid
+            .display_with(|(x, _), _| (x == 1).then_some(Colour::Red))
+            .to_string();
+        assert_eq!(s, "a\x1b[31mb\x1b[0mc\nd\x1b[31me\x1b[0mf");
+    }
+}
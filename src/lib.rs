@@ -1,4 +1,16 @@
+pub mod cycles;
 mod day;
+pub mod generate;
+pub mod graph;
+pub mod grid;
+pub mod matcher;
+pub mod math;
+pub mod parse;
+pub mod ranges;
+pub mod scan;
+pub mod shrink;
+pub mod style;
 pub mod template;
+pub mod viz;
 
 pub use day::*;
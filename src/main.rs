@@ -1,66 +1,128 @@
-use advent_of_code::template::commands::{all, download, read, scaffold, solve};
+use advent_of_code::template::commands::{
+    all, diff_test, download, gen, read, scaffold, shrink, solve,
+};
+use advent_of_code::template::EXIT_USAGE;
 use args::{parse, AppArguments};
 
 mod args {
-    use std::process;
+    use std::{env, ffi::OsString, time::Duration};
 
-    use advent_of_code::Day;
+    use advent_of_code::{
+        generate::Size,
+        style::{self, ColorChoice},
+        template::commands::solve,
+        template::{readme_benchmarks::SortOrder, report::Format},
+        viz::Visualize,
+        Day,
+    };
 
     pub enum AppArguments {
+        DiffTest {
+            day: Day,
+            reference: String,
+            release: bool,
+            timeout: Option<Duration>,
+        },
         Download {
             day: Day,
         },
+        Gen {
+            day: Day,
+            seed: u64,
+            size: Size,
+        },
         Read {
             day: Day,
         },
         Scaffold {
             day: Day,
         },
+        Shrink {
+            day: Day,
+            part: u8,
+            against: String,
+        },
         Solve {
             day: Day,
-            release: bool,
-            time: bool,
-            submit: Option<u8>,
+            options: solve::Options,
         },
         All {
             release: bool,
             time: bool,
+            sort: SortOrder,
+            report: Option<(String, Format)>,
+            timeout: Option<Duration>,
         },
     }
 
     pub fn parse() -> Result<AppArguments, Box<dyn std::error::Error>> {
-        let mut args = pico_args::Arguments::from_env();
+        // arguments after `--` belong to the solution, e.g. `cargo solve 2 -- --explain`.
+        let mut raw: Vec<OsString> = env::args_os().skip(1).collect();
+        let solution_args = match raw.iter().position(|arg| arg == "--") {
+            Some(index) => raw
+                .split_off(index)
+                .into_iter()
+                .skip(1)
+                .map(|arg| arg.to_string_lossy().into_owned())
+                .collect(),
+            None => vec![],
+        };
+        let mut args = pico_args::Arguments::from_vec(raw);
 
         let app_args = match args.subcommand()?.as_deref() {
             Some("all") => AppArguments::All {
                 release: args.contains("--release"),
                 time: args.contains("--time"),
+                sort: args.opt_value_from_str("--sort")?.unwrap_or_default(),
+                report: parse_report(&mut args)?,
+                timeout: parse_timeout(&mut args)?,
+            },
+            Some("diff-test") => AppArguments::DiffTest {
+                day: args.free_from_str()?,
+                reference: args.value_from_str("--reference")?,
+                release: args.contains("--release"),
+                timeout: parse_timeout(&mut args)?,
             },
             Some("download") => AppArguments::Download {
                 day: args.free_from_str()?,
             },
+            Some("gen") => AppArguments::Gen {
+                day: args.free_from_str()?,
+                seed: args.opt_value_from_str("--seed")?.unwrap_or(0),
+                size: args.opt_value_from_str("--size")?.unwrap_or_default(),
+            },
             Some("read") => AppArguments::Read {
                 day: args.free_from_str()?,
             },
             Some("scaffold") => AppArguments::Scaffold {
                 day: args.free_from_str()?,
             },
+            Some("shrink") => AppArguments::Shrink {
+                day: args.free_from_str()?,
+                part: parse_part(&mut args)?,
+                against: args.value_from_str("--against")?,
+            },
             Some("solve") => AppArguments::Solve {
                 day: args.free_from_str()?,
-                release: args.contains("--release"),
-                submit: args.opt_value_from_str("--submit")?,
-                time: args.contains("--time"),
+                options: solve::Options {
+                    release: args.contains("--release"),
+                    submit_part: args.opt_value_from_str("--submit")?,
+                    time: args.contains("--time"),
+                    normalize: args.contains("--normalize"),
+                    timeout: parse_timeout(&mut args)?,
+                    visualize: parse_visualize(&mut args)?,
+                    variants: args.contains("--variants"),
+                    args: solution_args,
+                },
             },
-            Some(x) => {
-                eprintln!("Unknown command: {x}");
-                process::exit(1);
-            }
-            None => {
-                eprintln!("No command specified.");
-                process::exit(1);
-            }
+            Some(x) => return Err(format!("unknown command: {x}").into()),
+            None => return Err("no command specified.".into()),
         };
 
+        // `--color` applies to every command.
+        let color: Option<ColorChoice> = args.opt_value_from_str("--color")?;
+        style::set_color_choice(color.unwrap_or_default());
+
         let remaining = args.finish();
         if !remaining.is_empty() {
             eprintln!("Warning: unknown argument(s): {remaining:?}.");
@@ -68,25 +130,96 @@ mod args {
 
         Ok(app_args)
     }
+
+    /// Parses the `--timeout <seconds>` option of `solve`, `all` and `diff-test`.
+    fn parse_timeout(
+        args: &mut pico_args::Arguments,
+    ) -> Result<Option<Duration>, Box<dyn std::error::Error>> {
+        let seconds: Option<f64> = args.opt_value_from_str("--timeout")?;
+
+        match seconds {
+            Some(seconds) => Duration::try_from_secs_f64(seconds)
+                .map(Some)
+                .map_err(|_| "expecting a positive number of seconds for `--timeout`.".into()),
+            None => Ok(None),
+        }
+    }
+
+    /// Parses the `--part <1|2>` option of `shrink`.
+    fn parse_part(args: &mut pico_args::Arguments) -> Result<u8, Box<dyn std::error::Error>> {
+        match args.value_from_str("--part")? {
+            part @ (1 | 2) => Ok(part),
+            _ => Err("expecting 1 or 2 for `--part`.".into()),
+        }
+    }
+
+    /// Parses the `--visualize` flag and the `--frames <file>` option of `solve`.
+    /// Exporting frames implies recording them.
+    fn parse_visualize(
+        args: &mut pico_args::Arguments,
+    ) -> Result<Option<Visualize>, Box<dyn std::error::Error>> {
+        let play = args.contains("--visualize");
+        let path: Option<String> = args.opt_value_from_str("--frames")?;
+
+        Ok(match path {
+            Some(path) => Some(Visualize::Export(path)),
+            None => play.then_some(Visualize::Play),
+        })
+    }
+
+    /// Parses the `--report <file>` and `--format <format>` options of `all`.
+    /// The format defaults to the one matching the file extension.
+    fn parse_report(
+        args: &mut pico_args::Arguments,
+    ) -> Result<Option<(String, Format)>, Box<dyn std::error::Error>> {
+        let path: Option<String> = args.opt_value_from_str("--report")?;
+        let format: Option<Format> = args.opt_value_from_str("--format")?;
+
+        let Some(path) = path else {
+            return Ok(None);
+        };
+
+        match format.or_else(|| Format::from_path(&path)) {
+            Some(format) => Ok(Some((path, format))),
+            None => Err(
+                "could not infer report format from file name, pass `--format csv|json|junit`."
+                    .into(),
+            ),
+        }
+    }
 }
 
 fn main() {
-    match parse() {
+    let result = match parse() {
         Err(err) => {
             eprintln!("Error: {err}");
-            std::process::exit(1);
+            std::process::exit(EXIT_USAGE);
         }
         Ok(args) => match args {
-            AppArguments::All { release, time } => all::handle(release, time),
+            AppArguments::All {
+                release,
+                time,
+                sort,
+                report,
+                timeout,
+            } => all::handle(release, time, sort, report, timeout),
+            AppArguments::DiffTest {
+                day,
+                reference,
+                release,
+                timeout,
+            } => diff_test::handle(day, &reference, release, timeout),
             AppArguments::Download { day } => download::handle(day),
+            AppArguments::Gen { day, seed, size } => gen::handle(day, seed, size),
             AppArguments::Read { day } => read::handle(day),
             AppArguments::Scaffold { day } => scaffold::handle(day),
-            AppArguments::Solve {
-                day,
-                release,
-                time,
-                submit,
-            } => solve::handle(day, release, time, submit),
+            AppArguments::Shrink { day, part, against } => shrink::handle(day, part, &against),
+            AppArguments::Solve { day, options } => solve::handle(day, options),
         },
     };
+
+    if let Err(err) = result {
+        eprintln!("Error: {err}");
+        std::process::exit(err.exit_code());
+    }
 }
@@ -0,0 +1,281 @@
+/// Module that finds many patterns at once with an Aho-Corasick automaton.
+use std::collections::VecDeque;
+
+/// A node without an edge for a byte while the trie is built.
+const MISSING: u32 = u32::MAX;
+
+/// Finds all occurrences of several patterns in one pass over a text, including overlapping ones,
+/// and the first or last occurrence by scanning from either end. Each pattern carries a value.
+/// Searching does not allocate.
+///
+/// ```
+/// # use advent_of_code::matcher::Matcher;
+/// let matcher = Matcher::new([("one", 1), ("eight", 8), ("1", 1)]);
+/// let values: Vec<u32> = matcher.find_iter("1oneight").map(|m| *m.value).collect();
+/// assert_eq!(values, [1, 1, 8]);
+/// assert_eq!(matcher.first("xoneight").map(|m| m.start), Some(1));
+/// assert_eq!(matcher.last("xoneight").map(|m| *m.value), Some(8));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Matcher<V> {
+    forward: Automaton,
+    /// Matches the reversed patterns, for scanning from the end.
+    backward: Automaton,
+    lengths: Vec<usize>,
+    values: Vec<V>,
+}
+
+/// An occurrence of a pattern at `start..end` (byte offsets).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Match<'m, V> {
+    pub start: usize,
+    pub end: usize,
+    pub value: &'m V,
+}
+
+impl<V> Matcher<V> {
+    /// Builds a matcher for `(pattern, value)` pairs. Panics if a pattern is empty.
+    /// If a pattern is given twice, the first value is used.
+    pub fn new<P: AsRef<[u8]>>(patterns: impl IntoIterator<Item = (P, V)>) -> Self {
+        let (patterns, values): (Vec<P>, Vec<V>) = patterns.into_iter().unzip();
+        let patterns: Vec<&[u8]> = patterns.iter().map(AsRef::as_ref).collect();
+        assert!(
+            patterns.iter().all(|p| !p.is_empty()),
+            "patterns must not be empty"
+        );
+
+        let reversed: Vec<Vec<u8>> = patterns
+            .iter()
+            .map(|p| p.iter().rev().copied().collect())
+            .collect();
+
+        Matcher {
+            forward: Automaton::new(patterns.iter().copied()),
+            backward: Automaton::new(reversed.iter().map(Vec::as_slice)),
+            lengths: patterns.iter().map(|p| p.len()).collect(),
+            values,
+        }
+    }
+
+    /// Returns every occurrence of every pattern, ordered by their end. Occurrences that end at
+    /// the same position are ordered from the longest to the shortest.
+    pub fn find_iter<'m, 'h>(&'m self, haystack: &'h str) -> Matches<'m, 'h, V> {
+        Matches {
+            matcher: self,
+            haystack: haystack.as_bytes(),
+            state: 0,
+            position: 0,
+            pending: None,
+        }
+    }
+
+    /// Returns the occurrence that starts first, of those the one that ends first.
+    /// Stops scanning as soon as no later occurrence could start before it.
+    pub fn first(&self, haystack: &str) -> Option<Match<'_, V>> {
+        let (start, end, pattern) = self.forward.leftmost(&self.lengths, haystack.bytes())?;
+        Some(self.at(start, end, pattern))
+    }
+
+    /// Returns the occurrence that ends last, of those the one that starts last.
+    /// Scans backwards from the end of `haystack`.
+    pub fn last(&self, haystack: &str) -> Option<Match<'_, V>> {
+        let (start, end, pattern) = self
+            .backward
+            .leftmost(&self.lengths, haystack.bytes().rev())?;
+        Some(self.at(haystack.len() - end, haystack.len() - start, pattern))
+    }
+
+    fn at(&self, start: usize, end: usize, pattern: u32) -> Match<'_, V> {
+        Match {
+            start,
+            end,
+            value: &self.values[pattern as usize],
+        }
+    }
+}
+
+/// The overlapping occurrences of patterns in a text, see [`Matcher::find_iter`].
+#[derive(Debug, Clone)]
+pub struct Matches<'m, 'h, V> {
+    matcher: &'m Matcher<V>,
+    haystack: &'h [u8],
+    state: u32,
+    position: usize,
+    /// The next node on the output chain of the current state.
+    pending: Option<u32>,
+}
+
+impl<'m, V> Iterator for Matches<'m, '_, V> {
+    type Item = Match<'m, V>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let automaton = &self.matcher.forward;
+
+        loop {
+            if let Some(node) = self.pending {
+                self.pending = automaton.dictionary[node as usize];
+                if let Some(pattern) = automaton.output[node as usize] {
+                    let start = self.position - self.matcher.lengths[pattern as usize];
+                    return Some(self.matcher.at(start, self.position, pattern));
+                }
+                continue;
+            }
+
+            let &byte = self.haystack.get(self.position)?;
+            self.position += 1;
+            self.state = automaton.step(self.state, byte);
+            self.pending = Some(self.state);
+        }
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// A trie of patterns whose missing edges follow the failure links, so that every byte of the text
+/// is a single table lookup.
+#[derive(Debug, Clone)]
+struct Automaton {
+    next: Vec<[u32; 256]>,
+    /// The longest pattern ending at each node.
+    output: Vec<Option<u32>>,
+    /// The nearest node on the failure chain of each node that has an output.
+    dictionary: Vec<Option<u32>>,
+    max_len: usize,
+}
+
+impl Automaton {
+    fn new<'p>(patterns: impl Iterator<Item = &'p [u8]>) -> Self {
+        let mut automaton = Automaton {
+            next: vec![[MISSING; 256]],
+            output: vec![None],
+            dictionary: vec![None],
+            max_len: 0,
+        };
+
+        for (index, pattern) in patterns.enumerate() {
+            let mut node = 0;
+            for &byte in pattern {
+                if automaton.next[node][byte as usize] == MISSING {
+                    automaton.next[node][byte as usize] = automaton.next.len() as u32;
+                    automaton.next.push([MISSING; 256]);
+                    automaton.output.push(None);
+                    automaton.dictionary.push(None);
+                }
+                node = automaton.next[node][byte as usize] as usize;
+            }
+            automaton.output[node].get_or_insert(index as u32);
+            automaton.max_len = automaton.max_len.max(pattern.len());
+        }
+
+        automaton.link();
+        automaton
+    }
+
+    /// Fills in the missing edges and the dictionary links breadth-first, so that the failure
+    /// target of every node is complete before its children need it.
+    fn link(&mut self) {
+        let mut failure = vec![0; self.next.len()];
+        let mut queue = VecDeque::new();
+
+        for byte in 0..256 {
+            match self.next[0][byte] {
+                MISSING => self.next[0][byte] = 0,
+                child => queue.push_back(child as usize),
+            }
+        }
+
+        while let Some(node) = queue.pop_front() {
+            let fail = failure[node];
+            self.dictionary[node] = if self.output[fail].is_some() {
+                Some(fail as u32)
+            } else {
+                self.dictionary[fail]
+            };
+
+            for byte in 0..256 {
+                match self.next[node][byte] {
+                    MISSING => self.next[node][byte] = self.next[fail][byte],
+                    child => {
+                        failure[child as usize] = self.next[fail][byte] as usize;
+                        queue.push_back(child as usize);
+                    }
+                }
+            }
+        }
+    }
+
+    fn step(&self, state: u32, byte: u8) -> u32 {
+        self.next[state as usize][byte as usize]
+    }
+
+    /// Returns `(start, end, pattern)` of the occurrence in `bytes` that starts first.
+    fn leftmost(
+        &self,
+        lengths: &[usize],
+        bytes: impl Iterator<Item = u8>,
+    ) -> Option<(usize, usize, u32)> {
+        let mut best: Option<(usize, usize, u32)> = None;
+        let mut state = 0;
+
+        for (i, byte) in bytes.enumerate() {
+            // occurrences ending after this byte start at `i + 1 - max_len` or later.
+            if best.is_some_and(|(start, _, _)| i + 1 >= start + self.max_len) {
+                break;
+            }
+
+            state = self.step(state, byte);
+            let mut node = Some(state);
+            while let Some(n) = node {
+                if let Some(pattern) = self.output[n as usize] {
+                    let start = i + 1 - lengths[pattern as usize];
+                    if best.is_none_or(|(best_start, _, _)| start < best_start) {
+                        best = Some((start, i + 1, pattern));
+                    }
+                }
+                node = self.dictionary[n as usize];
+            }
+        }
+
+        best
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::Matcher;
+
+    #[test]
+    fn finds_overlapping_matches() {
+        let matcher = Matcher::new([("he", 'a'), ("she", 'b'), ("his", 'c'), ("hers", 'd')]);
+        let matches: Vec<(usize, usize, char)> = matcher
+            .find_iter("ushers")
+            .map(|m| (m.start, m.end, *m.value))
+            .collect();
+        assert_eq!(matches, [(1, 4, 'b'), (2, 4, 'a'), (2, 6, 'd')]);
+
+        assert_eq!(matcher.find_iter("xyz").count(), 0);
+        assert_eq!(matcher.find_iter("").count(), 0);
+    }
+
+    #[test]
+    fn finds_first_and_last_matches() {
+        // "bc" ends before "abcd", but starts after it.
+        let matcher = Matcher::new([("abcd", 1), ("bc", 2), ("cd", 3), ("d", 4)]);
+        let first = matcher.first("xabcdx").unwrap();
+        assert_eq!((first.start, first.end, *first.value), (1, 5, 1));
+        let last = matcher.last("xabcdx").unwrap();
+        assert_eq!((last.start, last.end, *last.value), (4, 5, 4));
+
+        assert_eq!(matcher.first("xyz"), None);
+        assert_eq!(matcher.last(""), None);
+    }
+
+    #[test]
+    fn keeps_the_first_value_of_duplicate_patterns() {
+        let matcher = Matcher::new([("ab", 1), ("ab", 2)]);
+        let values: Vec<i32> = matcher.find_iter("abab").map(|m| *m.value).collect();
+        assert_eq!(values, [1, 1]);
+    }
+}
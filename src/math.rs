@@ -0,0 +1,360 @@
+/// Module that provides number theory helpers and exact integer solutions to quadratic
+/// inequalities.
+use std::ops::{Add, Div, Mul, Neg, RangeInclusive, Rem, Sub};
+
+/// A primitive integer type, so that number theory helpers work with whatever type a solution uses.
+pub trait Integer:
+    Copy
+    + Ord
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Rem<Output = Self>
+{
+    const ZERO: Self;
+    const ONE: Self;
+
+    /// Returns the largest integer whose square is at most `self`. Panics for negative values.
+    #[must_use]
+    fn isqrt(self) -> Self;
+}
+
+/// A signed primitive integer type.
+pub trait Signed: Integer + Neg<Output = Self> {}
+
+macro_rules! impl_integer {
+    ($($t:ty),*) => {
+        $(
+            impl Integer for $t {
+                const ZERO: Self = 0;
+                const ONE: Self = 1;
+
+                fn isqrt(self) -> Self {
+                    <$t>::isqrt(self)
+                }
+            }
+        )*
+    };
+}
+
+impl_integer!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+impl Signed for i8 {}
+impl Signed for i16 {}
+impl Signed for i32 {}
+impl Signed for i64 {}
+impl Signed for i128 {}
+impl Signed for isize {}
+
+fn abs<T: Integer>(x: T) -> T {
+    if x < T::ZERO {
+        T::ZERO - x
+    } else {
+        x
+    }
+}
+
+/// Returns the greatest common divisor of `a` and `b`, which is never negative.
+///
+/// ```
+/// # use advent_of_code::math::gcd;
+/// assert_eq!(gcd(12u32, 18), 6);
+/// assert_eq!(gcd(-4i64, 6), 2);
+/// ```
+#[must_use]
+pub fn gcd<T: Integer>(mut a: T, mut b: T) -> T {
+    while b != T::ZERO {
+        (a, b) = (b, a % b);
+    }
+    abs(a)
+}
+
+/// Returns the least common multiple of `a` and `b`, or zero if either of them is zero.
+#[must_use]
+pub fn lcm<T: Integer>(a: T, b: T) -> T {
+    if a == T::ZERO || b == T::ZERO {
+        return T::ZERO;
+    }
+    abs(a / gcd(a, b) * b)
+}
+
+/// Returns the least common multiple of all `values`, or [`None`] if there are none.
+pub fn lcm_all<T: Integer>(values: impl IntoIterator<Item = T>) -> Option<T> {
+    values.into_iter().reduce(lcm)
+}
+
+/// Returns `(g, x, y)` with `g = gcd(a, b)` and `a * x + b * y = g`.
+#[must_use]
+pub fn extended_gcd<T: Signed>(a: T, b: T) -> (T, T, T) {
+    let (mut r0, mut r1) = (a, b);
+    let (mut x0, mut x1) = (T::ONE, T::ZERO);
+    let (mut y0, mut y1) = (T::ZERO, T::ONE);
+
+    while r1 != T::ZERO {
+        let q = r0 / r1;
+        (r0, r1) = (r1, r0 - q * r1);
+        (x0, x1) = (x1, x0 - q * x1);
+        (y0, y1) = (y1, y0 - q * y1);
+    }
+
+    if r0 < T::ZERO {
+        (-r0, -x0, -y0)
+    } else {
+        (r0, x0, y0)
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// Returns `a * b mod m` for `0 <= a, b < m`, without overflowing for large moduli.
+fn mul_mod(a: i128, b: i128, m: i128) -> i128 {
+    if let Some(product) = a.checked_mul(b) {
+        return product % m;
+    }
+
+    let (mut a, mut b, mut result) = (a, b, 0);
+    while b > 0 {
+        if b & 1 == 1 {
+            result = (result + a) % m;
+        }
+        a = (a + a) % m;
+        b >>= 1;
+    }
+    result
+}
+
+/// Returns `base^exp mod modulus`.
+///
+/// ```
+/// # use advent_of_code::math::mod_pow;
+/// assert_eq!(mod_pow(3, 200, 13), 9);
+/// ```
+#[must_use]
+pub fn mod_pow(base: u64, mut exp: u64, modulus: u64) -> u64 {
+    let modulus = u128::from(modulus);
+    let mut base = u128::from(base) % modulus;
+    let mut result = 1 % modulus;
+
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = result * base % modulus;
+        }
+        base = base * base % modulus;
+        exp >>= 1;
+    }
+
+    // Smaller than `modulus`, which came from a `u64`.
+    result as u64
+}
+
+/// Returns `x` in `0..m` with `a * x ≡ 1 (mod m)`, or [`None`] if `a` and `m` are not coprime.
+#[must_use]
+pub fn mod_inverse(a: i128, m: i128) -> Option<i128> {
+    let (g, x, _) = extended_gcd(a.rem_euclid(m), m);
+    (g == 1).then(|| x.rem_euclid(m))
+}
+
+/// Solves a system of congruences `x ≡ residue (mod modulus)` with the Chinese remainder theorem.
+/// The moduli do not have to be coprime.
+///
+/// Returns `(x, m)` where `x` in `0..m` is the smallest solution and `m` is the least common multiple
+/// of all moduli, i.e. the solutions are `x + k * m`. Returns [`None`] if the congruences contradict
+/// each other or `m` does not fit into an `i128`.
+///
+/// ```
+/// # use advent_of_code::math::crt;
+/// assert_eq!(crt([(2, 3), (3, 5), (2, 7)]), Some((23, 105)));
+/// assert_eq!(crt([(2, 4), (4, 6)]), Some((10, 12)));
+/// assert_eq!(crt([(1, 4), (2, 6)]), None);
+/// ```
+pub fn crt(congruences: impl IntoIterator<Item = (i128, i128)>) -> Option<(i128, i128)> {
+    congruences
+        .into_iter()
+        .try_fold((0, 1), |(r1, m1), (r2, m2)| {
+            let r2 = r2.rem_euclid(m2);
+            let g = gcd(m1, m2);
+            let diff = r2 - r1;
+            if diff % g != 0 {
+                return None;
+            }
+
+            // Solve r1 + m1 * k ≡ r2 (mod m2) for k.
+            let m2_g = m2 / g;
+            let inverse = mod_inverse(m1 / g, m2_g)?;
+            let k = mul_mod((diff / g).rem_euclid(m2_g), inverse, m2_g);
+
+            let m = (m1 / g).checked_mul(m2)?;
+            let x = mul_mod(m1 % m, k, m);
+            Some(((r1 + x) % m, m))
+        })
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// Evaluates `a·x² + b·x + c` in Horner form. Close to the roots `a·x + b` is small, so this does not
+/// overflow even where `a·x²` alone would.
+fn eval_quadratic(a: i128, b: i128, c: i128, x: i128) -> i128 {
+    (a * x + b) * x + c
+}
+
+/// Returns the integers `x` with `a·x² + b·x + c < 0`, or [`None`] if there are none.
+/// Uses exact integer arithmetic, so it stays correct where floating point roots would round wrongly.
+/// The discriminant is computed in a `u128`, so `b` may use the whole `u64` range, e.g. for
+/// `x·(t - x) > d` with `u64` values of `t` and `d`.
+///
+/// Panics if `a` is not positive, as the solutions would not form a single range, or if the
+/// discriminant `b² - 4·a·c` does not fit into a `u128`.
+///
+/// ```
+/// # use advent_of_code::math::quadratic_negative;
+/// // Race of 7ms with a record of 9mm: holding the button for x ms travels x·(7 - x) > 9.
+/// assert_eq!(quadratic_negative(1, -7, 9), Some(2..=5));
+/// ```
+#[must_use]
+pub fn quadratic_negative(a: i128, b: i128, c: i128) -> Option<RangeInclusive<i128>> {
+    quadratic_range(a, b, c, |y| y < 0)
+}
+
+/// Returns the integers `x` with `a·x² + b·x + c ≤ 0`, or [`None`] if there are none.
+/// See [`quadratic_negative`].
+#[must_use]
+pub fn quadratic_non_positive(a: i128, b: i128, c: i128) -> Option<RangeInclusive<i128>> {
+    quadratic_range(a, b, c, |y| y <= 0)
+}
+
+fn quadratic_range(
+    a: i128,
+    b: i128,
+    c: i128,
+    holds: impl Fn(i128) -> bool,
+) -> Option<RangeInclusive<i128>> {
+    assert!(a > 0, "the leading coefficient must be positive, got {a}");
+
+    let too_large = || panic!("the discriminant of {a}·x² + {b}·x + {c} does not fit into a u128");
+    let b_squared = b
+        .unsigned_abs()
+        .checked_mul(b.unsigned_abs())
+        .unwrap_or_else(too_large);
+    let four_ac = a
+        .unsigned_abs()
+        .checked_mul(c.unsigned_abs())
+        .and_then(|ac| ac.checked_mul(4))
+        .unwrap_or_else(too_large);
+    let discriminant = if c < 0 {
+        b_squared.checked_add(four_ac).unwrap_or_else(too_large)
+    } else {
+        // a negative discriminant has no real roots.
+        b_squared.checked_sub(four_ac)?
+    };
+
+    // The real roots are (-b ± √d) / 2a. Start from the integer approximations and
+    // step until the bounds are exact; this takes at most a couple of steps.
+    let root = i128::try_from(discriminant.isqrt()).expect("the root of a u128 fits into an i128");
+    let mut low = (-b - root).div_euclid(2 * a);
+    let mut high = (-b + root).div_euclid(2 * a) + 1;
+
+    while holds(eval_quadratic(a, b, c, low - 1)) {
+        low -= 1;
+    }
+    while low <= high && !holds(eval_quadratic(a, b, c, low)) {
+        low += 1;
+    }
+    while holds(eval_quadratic(a, b, c, high + 1)) {
+        high += 1;
+    }
+    while high >= low && !holds(eval_quadratic(a, b, c, high)) {
+        high -= 1;
+    }
+
+    (low <= high).then_some(low..=high)
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{
+        crt, extended_gcd, gcd, lcm, lcm_all, mod_inverse, mod_pow, quadratic_negative,
+        quadratic_non_positive, Integer,
+    };
+
+    #[test]
+    fn computes_gcd_and_lcm() {
+        assert_eq!(gcd(0u8, 5), 5);
+        assert_eq!(gcd(48i32, -18), 6);
+        assert_eq!(lcm(4u64, 6), 12);
+        assert_eq!(lcm(-4i64, 6), 12);
+        assert_eq!(lcm(0u32, 6), 0);
+        assert_eq!(lcm_all([2u64, 3, 4]), Some(12));
+        assert_eq!(lcm_all(Vec::<u64>::new()), None);
+    }
+
+    #[test]
+    fn solves_bezout_identity() {
+        let (g, x, y) = extended_gcd(240i64, 46);
+        assert_eq!(g, 2);
+        assert_eq!(240 * x + 46 * y, 2);
+
+        let (g, x, y) = extended_gcd(-12i32, 8);
+        assert_eq!(g, 4);
+        assert_eq!(-12 * x + 8 * y, 4);
+    }
+
+    #[test]
+    fn computes_modular_arithmetic() {
+        assert_eq!(mod_pow(2, 10, 1000), 24);
+        assert_eq!(mod_pow(5, 0, 1), 0);
+        assert_eq!(mod_pow(u64::MAX, u64::MAX, u64::MAX - 1), 1);
+        assert_eq!(mod_inverse(3, 11), Some(4));
+        assert_eq!(mod_inverse(-3, 11), Some(7));
+        assert_eq!(mod_inverse(4, 8), None);
+    }
+
+    #[test]
+    fn solves_congruences() {
+        assert_eq!(crt([(0, 3), (3, 4), (4, 5)]), Some((39, 60)));
+        assert_eq!(crt([(-1, 4)]), Some((3, 4)));
+        assert_eq!(crt([(3, 6), (1, 4)]), Some((9, 12)));
+        assert_eq!(crt([(0, 2), (1, 4)]), None);
+        assert_eq!(crt([]), Some((0, 1)));
+
+        let (p, q) = (1_000_000_007i128, 998_244_353i128);
+        let x = 123_456_789_012_345_678i128;
+        assert_eq!(crt([(x % p, p), (x % q, q)]), Some((x, p * q)));
+    }
+
+    #[test]
+    fn computes_exact_square_roots() {
+        assert_eq!(Integer::isqrt(99u32), 9);
+        assert_eq!(Integer::isqrt(100u64), 10);
+        assert_eq!(Integer::isqrt(u128::MAX), u128::from(u64::MAX));
+    }
+
+    #[test]
+    fn solves_quadratic_inequalities() {
+        // x·(30 - x) > 200
+        assert_eq!(quadratic_negative(1, -30, 200), Some(11..=19));
+        assert_eq!(quadratic_non_positive(1, -30, 200), Some(10..=20));
+        // x² + 1 never is negative, x² only is zero at 0.
+        assert_eq!(quadratic_negative(1, 0, 1), None);
+        assert_eq!(quadratic_negative(1, 0, 0), None);
+        assert_eq!(quadratic_non_positive(1, 0, 0), Some(0..=0));
+        // Large values where f64 roots are off by one.
+        let t = 100_000_000_000i128;
+        let d = t * t / 4 - 1;
+        assert_eq!(quadratic_negative(1, -t, d), Some(t / 2..=t / 2));
+        assert_eq!(quadratic_non_positive(4, -4, -3), Some(0..=1));
+        // b² only fits into a u128.
+        let t = i128::from(u64::MAX);
+        assert_eq!(quadratic_negative(1, -t, 0), Some(1..=t - 1));
+        assert_eq!(quadratic_negative(1, -t, t), Some(2..=t - 2));
+        assert_eq!(quadratic_non_positive(1, -t, -1), Some(0..=t));
+    }
+
+    #[test]
+    #[should_panic(expected = "does not fit into a u128")]
+    fn rejects_too_large_discriminants() {
+        let _ = quadratic_negative(1, i128::MAX, 0);
+    }
+}
@@ -0,0 +1,292 @@
+/// Module that splits puzzle inputs into lines and sections and extracts numbers from them, with
+/// errors that point at the offending line.
+use std::error::Error;
+use std::fmt::Display;
+use std::str::FromStr;
+
+/// An error which can be returned when parsing a puzzle input, naming the line it occurred on.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// The 1-based number of the offending line.
+    pub line: usize,
+    pub message: String,
+}
+
+impl Error for ParseError {}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+/// Prints why a puzzle input could not be parsed, so that a solution part can return `None` with `?`.
+pub fn report<T>(result: Result<T, ParseError>) -> Option<T> {
+    result.map_err(|e| eprintln!("Error: {e}")).ok()
+}
+
+/// A line of a puzzle input together with its 1-based line number, so that parse errors can point at it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Line<'a> {
+    pub number: usize,
+    pub text: &'a str,
+}
+
+/// Iterates the lines of `input`, numbered from 1.
+pub fn lines(input: &str) -> impl Iterator<Item = Line<'_>> {
+    numbered(input, 1)
+}
+
+fn numbered(input: &str, first: usize) -> impl Iterator<Item = Line<'_>> {
+    input.lines().enumerate().map(move |(i, text)| Line {
+        number: first + i,
+        text,
+    })
+}
+
+/// A block of consecutive non-blank lines, see [`sections`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Section<'a> {
+    /// The 1-based number of the first line of the section.
+    pub line: usize,
+    pub text: &'a str,
+}
+
+impl<'a> Section<'a> {
+    /// Iterates the lines of the section, numbered as in the whole input.
+    pub fn lines(&self) -> impl Iterator<Item = Line<'a>> {
+        numbered(self.text, self.line)
+    }
+}
+
+/// Iterates the sections of `input` that are separated by one or more blank lines.
+///
+/// ```
+/// # use advent_of_code::parse::sections;
+/// let sections: Vec<_> = sections("seeds: 1 2\n\nmap:\n1 2 3\n").collect();
+/// assert_eq!(sections[1].line, 3);
+/// assert_eq!(sections[1].text, "map:\n1 2 3");
+/// ```
+pub fn sections(input: &str) -> impl Iterator<Item = Section<'_>> {
+    let mut lines = input.split_inclusive('\n').enumerate().peekable();
+    let mut offset = 0;
+
+    std::iter::from_fn(move || {
+        // Skip blank lines before the section.
+        while let Some((_, line)) = lines.next_if(|(_, line)| line.trim().is_empty()) {
+            offset += line.len();
+        }
+
+        let (index, _) = *lines.peek()?;
+        let start = offset;
+        while let Some((_, line)) = lines.next_if(|(_, line)| !line.trim().is_empty()) {
+            offset += line.len();
+        }
+
+        Some(Section {
+            line: index + 1,
+            text: input[start..offset].trim_end_matches(['\n', '\r']),
+        })
+    })
+}
+
+/// Iterates the runs of ASCII digits in `text` as byte ranges.
+fn digit_runs(text: &str) -> impl Iterator<Item = std::ops::Range<usize>> + '_ {
+    let bytes = text.as_bytes();
+    let mut i = 0;
+
+    std::iter::from_fn(move || {
+        while i < bytes.len() && !bytes[i].is_ascii_digit() {
+            i += 1;
+        }
+        if i == bytes.len() {
+            return None;
+        }
+
+        let start = i;
+        while i < bytes.len() && bytes[i].is_ascii_digit() {
+            i += 1;
+        }
+        Some(start..i)
+    })
+}
+
+impl<'a> Line<'a> {
+    /// Creates an error that points at this line.
+    #[must_use]
+    pub fn error(&self, message: impl Display) -> ParseError {
+        ParseError {
+            line: self.number,
+            message: message.to_string(),
+        }
+    }
+
+    fn parse_number<T: FromStr>(&self, number: &str) -> Result<T, ParseError> {
+        number
+            .parse()
+            .map_err(|_| self.error(format!("\"{number}\" is not a valid number")))
+    }
+
+    /// Returns all unsigned integers in the line, ignoring any other characters.
+    ///
+    /// ```
+    /// # use advent_of_code::parse::lines;
+    /// let line = lines("Card 1: 41 48 | 83 -86").next().unwrap();
+    /// assert_eq!(line.unsigned::<u32>().unwrap(), [1, 41, 48, 83, 86]);
+    /// ```
+    pub fn unsigned<T: FromStr>(&self) -> Result<Vec<T>, ParseError> {
+        digit_runs(self.text)
+            .map(|run| self.parse_number(&self.text[run]))
+            .collect()
+    }
+
+    /// Returns all integers in the line, treating a `-` directly in front of digits as a sign.
+    ///
+    /// ```
+    /// # use advent_of_code::parse::lines;
+    /// let line = lines("x=-3, y=14").next().unwrap();
+    /// assert_eq!(line.signed::<i64>().unwrap(), [-3, 14]);
+    /// ```
+    pub fn signed<T: FromStr>(&self) -> Result<Vec<T>, ParseError> {
+        digit_runs(self.text)
+            .map(|run| {
+                let is_negative = run.start > 0 && self.text.as_bytes()[run.start - 1] == b'-';
+                let start = if is_negative {
+                    run.start - 1
+                } else {
+                    run.start
+                };
+                self.parse_number(&self.text[start..run.end])
+            })
+            .collect()
+    }
+
+    /// Returns exactly `N` unsigned integers, or an error if the line contains a different number of them.
+    ///
+    /// ```
+    /// # use advent_of_code::parse::lines;
+    /// let line = lines("50 98 2").next().unwrap();
+    /// let [destination, source, length] = line.unsigned_array::<u64, 3>().unwrap();
+    /// assert_eq!((destination, source, length), (50, 98, 2));
+    /// ```
+    pub fn unsigned_array<T: FromStr, const N: usize>(&self) -> Result<[T; N], ParseError> {
+        self.collect_array(self.unsigned()?)
+    }
+
+    /// Returns exactly `N` integers like [`Line::signed`], or an error if the line contains a different number of them.
+    pub fn signed_array<T: FromStr, const N: usize>(&self) -> Result<[T; N], ParseError> {
+        self.collect_array(self.signed()?)
+    }
+
+    fn collect_array<T, const N: usize>(&self, numbers: Vec<T>) -> Result<[T; N], ParseError> {
+        let count = numbers.len();
+        numbers
+            .try_into()
+            .map_err(|_| self.error(format!("expected {N} numbers, found {count}")))
+    }
+
+    /// Splits a `"label: payload"` line at the first colon. Both parts are trimmed
+    /// and keep the number of this line.
+    ///
+    /// ```
+    /// # use advent_of_code::parse::lines;
+    /// let line = lines("Game 12: 3 blue, 4 red").next().unwrap();
+    /// let (label, payload) = line.split_label().unwrap();
+    /// assert_eq!(label.text, "Game 12");
+    /// assert_eq!(payload.text, "3 blue, 4 red");
+    /// ```
+    pub fn split_label(&self) -> Result<(Line<'a>, Line<'a>), ParseError> {
+        let (label, payload) = self
+            .text
+            .split_once(':')
+            .ok_or_else(|| self.error("expected a \"label: payload\" line"))?;
+
+        Ok((self.with_text(label.trim()), self.with_text(payload.trim())))
+    }
+
+    /// Returns a line with the same number, e.g. for a part of this line.
+    #[must_use]
+    pub fn with_text(&self, text: &'a str) -> Line<'a> {
+        Line {
+            number: self.number,
+            text,
+        }
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{lines, sections, ParseError};
+
+    #[test]
+    fn numbers_lines() {
+        let numbers: Vec<_> = lines("a\nb\n")
+            .map(|line| (line.number, line.text))
+            .collect();
+        assert_eq!(numbers, [(1, "a"), (2, "b")]);
+    }
+
+    #[test]
+    fn splits_sections() {
+        let input = "\nseeds: 1\n\n\na:\n1 2\r\n3 4\r\n\r\nb:\n";
+        let sections: Vec<_> = sections(input).collect();
+        assert_eq!(sections.len(), 3);
+        assert_eq!((sections[0].line, sections[0].text), (2, "seeds: 1"));
+        assert_eq!((sections[1].line, sections[1].text), (5, "a:\n1 2\r\n3 4"));
+        assert_eq!((sections[2].line, sections[2].text), (9, "b:"));
+
+        let numbers: Vec<_> = sections[1].lines().map(|line| line.number).collect();
+        assert_eq!(numbers, [5, 6, 7]);
+        assert_eq!(super::sections("\n\n").count(), 0);
+    }
+
+    #[test]
+    fn extracts_integers() {
+        let line = lines("move -12 from 3-4 to +5, 007").next().unwrap();
+        assert_eq!(line.unsigned::<u32>().unwrap(), [12, 3, 4, 5, 7]);
+        assert_eq!(line.signed::<i32>().unwrap(), [-12, 3, -4, 5, 7]);
+
+        let overflow = lines("1\n2 300").nth(1).unwrap();
+        assert_eq!(
+            overflow.unsigned::<u8>(),
+            Err(ParseError {
+                line: 2,
+                message: "\"300\" is not a valid number".into()
+            })
+        );
+    }
+
+    #[test]
+    fn extracts_fixed_number_of_integers() {
+        let line = lines("\n1, -2, 3").nth(1).unwrap();
+        assert_eq!(line.signed_array::<i8, 3>().unwrap(), [1, -2, 3]);
+        assert_eq!(
+            line.unsigned_array::<u8, 2>(),
+            Err(ParseError {
+                line: 2,
+                message: "expected 2 numbers, found 3".into()
+            })
+        );
+    }
+
+    #[test]
+    fn splits_labels() {
+        let line = lines("Card  1: 41 48 | 83").next().unwrap();
+        let (label, payload) = line.split_label().unwrap();
+        assert_eq!(label.text, "Card  1");
+        assert_eq!(label.unsigned_array::<u32, 1>().unwrap(), [1]);
+        assert_eq!(payload.text, "41 48 | 83");
+
+        let error = lines("\nno label")
+            .nth(1)
+            .unwrap()
+            .split_label()
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 2: expected a \"label: payload\" line"
+        );
+    }
+}
@@ -0,0 +1,476 @@
+/// Module that provides interval sets and piecewise-linear maps on ranges of integers.
+use std::ops::Range;
+
+use crate::math::Integer;
+
+/// A set of integers, stored as sorted, disjoint and non-adjacent half-open ranges.
+///
+/// ```
+/// # use advent_of_code::ranges::IntervalSet;
+/// let set = IntervalSet::from_ranges([5..8, 0..3, 2..4]);
+/// assert_eq!(set.ranges(), [0..4, 5..8]);
+/// assert_eq!(set.len(), 7);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct IntervalSet<T> {
+    ranges: Vec<Range<T>>,
+}
+
+impl<T: Integer> IntervalSet<T> {
+    #[must_use]
+    pub fn new() -> Self {
+        Self { ranges: vec![] }
+    }
+
+    /// Creates a set containing every integer of every range. Ranges may overlap, empty ranges are ignored.
+    pub fn from_ranges(ranges: impl IntoIterator<Item = Range<T>>) -> Self {
+        let mut ranges: Vec<Range<T>> = ranges.into_iter().filter(|r| r.start < r.end).collect();
+        ranges.sort_unstable_by_key(|r| r.start);
+
+        let mut merged: Vec<Range<T>> = Vec::with_capacity(ranges.len());
+        for range in ranges {
+            match merged.last_mut() {
+                Some(last) if range.start <= last.end => last.end = last.end.max(range.end),
+                _ => merged.push(range),
+            }
+        }
+
+        Self { ranges: merged }
+    }
+
+    /// Returns the ranges of the set in ascending order.
+    #[must_use]
+    pub fn ranges(&self) -> &[Range<T>] {
+        &self.ranges
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    /// Returns the number of integers in the set.
+    #[must_use]
+    pub fn len(&self) -> T {
+        self.ranges
+            .iter()
+            .fold(T::ZERO, |acc, r| acc + (r.end - r.start))
+    }
+
+    /// Returns the smallest integer in the set.
+    #[must_use]
+    pub fn min(&self) -> Option<T> {
+        self.ranges.first().map(|r| r.start)
+    }
+
+    #[must_use]
+    pub fn contains(&self, value: T) -> bool {
+        let i = self.ranges.partition_point(|r| r.end <= value);
+        self.ranges.get(i).is_some_and(|r| r.start <= value)
+    }
+
+    pub fn insert(&mut self, range: Range<T>) {
+        *self = self.union(&Self::from_ranges([range]));
+    }
+
+    #[must_use]
+    pub fn union(&self, other: &Self) -> Self {
+        Self::from_ranges(self.ranges.iter().chain(&other.ranges).cloned())
+    }
+
+    #[must_use]
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut ranges = vec![];
+        let (mut i, mut j) = (0, 0);
+
+        while let (Some(a), Some(b)) = (self.ranges.get(i), other.ranges.get(j)) {
+            let start = a.start.max(b.start);
+            let end = a.end.min(b.end);
+            if start < end {
+                ranges.push(start..end);
+            }
+            if a.end < b.end {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+
+        Self { ranges }
+    }
+
+    /// Returns the integers that are in `self`, but not in `other`.
+    #[must_use]
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut ranges = vec![];
+        let mut j = 0;
+
+        for a in &self.ranges {
+            let mut start = a.start;
+            // Skip ranges of `other` that end before this one starts.
+            while other.ranges.get(j).is_some_and(|b| b.end <= start) {
+                j += 1;
+            }
+
+            let mut k = j;
+            while let Some(b) = other.ranges.get(k).filter(|b| b.start < a.end) {
+                if start < b.start {
+                    ranges.push(start..b.start);
+                }
+                start = start.max(b.end);
+                k += 1;
+            }
+
+            if start < a.end {
+                ranges.push(start..a.end);
+            }
+        }
+
+        Self { ranges }
+    }
+}
+
+impl<T: Integer> FromIterator<Range<T>> for IntervalSet<T> {
+    fn from_iter<I: IntoIterator<Item = Range<T>>>(iter: I) -> Self {
+        Self::from_ranges(iter)
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// A part of a [`RangeMap`] that shifts the integers of `source` so that `source.start` becomes `destination`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Segment<T> {
+    pub source: Range<T>,
+    pub destination: T,
+}
+
+impl<T: Integer> Segment<T> {
+    fn apply(&self, value: T) -> T {
+        value - self.source.start + self.destination
+    }
+}
+
+/// A piecewise-linear map over the integers. Every segment shifts a range of integers,
+/// integers that are not covered by a segment map to themselves.
+///
+/// Ranges are mapped as a whole by splitting them at segment boundaries, so the cost depends on the
+/// number of segments instead of the number of integers.
+///
+/// ```
+/// # use advent_of_code::ranges::{IntervalSet, RangeMap};
+/// let mut map = RangeMap::new();
+/// map.insert(98..100, 50);
+/// map.insert(50..98, 52);
+/// assert_eq!(map.apply(79), 81);
+/// assert_eq!(map.apply(10), 10);
+///
+/// let seeds = IntervalSet::from_ranges([79..93]);
+/// assert_eq!(map.apply_set(&seeds).ranges(), [81..95]);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct RangeMap<T> {
+    /// Sorted by `source.start`, sources do not overlap.
+    segments: Vec<Segment<T>>,
+}
+
+impl<T: Integer> RangeMap<T> {
+    #[must_use]
+    pub fn new() -> Self {
+        Self { segments: vec![] }
+    }
+
+    /// Adds a segment mapping `source` onto the range starting at `destination`. Empty sources are ignored.
+    ///
+    /// Panics if `source` overlaps the source of another segment, see [`RangeMap::try_insert`].
+    pub fn insert(&mut self, source: Range<T>, destination: T) {
+        assert!(
+            self.try_insert(source, destination).is_ok(),
+            "segment sources must not overlap"
+        );
+    }
+
+    /// Adds a segment like [`RangeMap::insert`], but returns the source of the segment that `source`
+    /// overlaps instead of panicking. The map is left unchanged then.
+    pub fn try_insert(&mut self, source: Range<T>, destination: T) -> Result<(), Range<T>> {
+        if source.start >= source.end {
+            return Ok(());
+        }
+
+        let i = self
+            .segments
+            .partition_point(|s| s.source.start < source.start);
+        if let Some(previous) = i.checked_sub(1).map(|i| &self.segments[i]) {
+            if previous.source.end > source.start {
+                return Err(previous.source.clone());
+            }
+        }
+        if let Some(next) = self.segments.get(i) {
+            if next.source.start < source.end {
+                return Err(next.source.clone());
+            }
+        }
+
+        self.segments.insert(
+            i,
+            Segment {
+                source,
+                destination,
+            },
+        );
+        Ok(())
+    }
+
+    /// Returns the segments in ascending order of their sources.
+    #[must_use]
+    pub fn segments(&self) -> &[Segment<T>] {
+        &self.segments
+    }
+
+    #[must_use]
+    pub fn apply(&self, value: T) -> T {
+        let i = self.segments.partition_point(|s| s.source.end <= value);
+        match self.segments.get(i) {
+            Some(segment) if segment.source.start <= value => segment.apply(value),
+            _ => value,
+        }
+    }
+
+    /// Splits `range` at segment boundaries. Every piece is returned with the segment that covers it,
+    /// or [`None`] for pieces that map to themselves.
+    fn pieces(&self, range: Range<T>) -> Vec<(Range<T>, Option<&Segment<T>>)> {
+        let mut pieces = vec![];
+        let mut start = range.start;
+        let mut i = self.segments.partition_point(|s| s.source.end <= start);
+
+        while start < range.end {
+            match self.segments.get(i) {
+                Some(segment) if segment.source.start <= start => {
+                    let end = segment.source.end.min(range.end);
+                    pieces.push((start..end, Some(segment)));
+                    start = end;
+                    i += 1;
+                }
+                Some(segment) if segment.source.start < range.end => {
+                    pieces.push((start..segment.source.start, None));
+                    start = segment.source.start;
+                }
+                _ => {
+                    pieces.push((start..range.end, None));
+                    start = range.end;
+                }
+            }
+        }
+
+        pieces
+    }
+
+    /// Maps every integer of `range`, returning the image as a set.
+    #[must_use]
+    pub fn apply_range(&self, range: Range<T>) -> IntervalSet<T> {
+        self.pieces(range)
+            .into_iter()
+            .map(|(piece, segment)| match segment {
+                Some(segment) => segment.apply(piece.start)..segment.apply(piece.end),
+                None => piece,
+            })
+            .collect()
+    }
+
+    /// Maps every integer of `set`, returning the image as a set.
+    #[must_use]
+    pub fn apply_set(&self, set: &IntervalSet<T>) -> IntervalSet<T> {
+        set.ranges()
+            .iter()
+            .flat_map(|range| self.apply_range(range.clone()).ranges)
+            .collect()
+    }
+
+    /// Returns every integer that `self` maps into `set`, i.e. the inverse image of `set`.
+    ///
+    /// ```
+    /// # use advent_of_code::ranges::{IntervalSet, RangeMap};
+    /// let mut map = RangeMap::new();
+    /// map.insert(98..100, 50);
+    /// map.insert(50..98, 52);
+    /// let set = IntervalSet::from_ranges([50..53]);
+    /// assert_eq!(map.preimage(&set).ranges(), [50..51, 98..100]);
+    /// ```
+    #[must_use]
+    pub fn preimage(&self, set: &IntervalSet<T>) -> IntervalSet<T> {
+        // Integers that are not covered by a segment map to themselves.
+        let sources: IntervalSet<T> = self.segments.iter().map(|s| s.source.clone()).collect();
+        let mut preimage = set.difference(&sources);
+
+        for segment in &self.segments {
+            let image = IntervalSet::from_ranges([
+                segment.apply(segment.source.start)..segment.apply(segment.source.end)
+            ]);
+            for range in image.intersection(set).ranges() {
+                preimage.insert(
+                    range.start - segment.destination + segment.source.start
+                        ..range.end - segment.destination + segment.source.start,
+                );
+            }
+        }
+
+        preimage
+    }
+
+    /// Returns the map that applies `self` first and `then` second.
+    ///
+    /// ```
+    /// # use advent_of_code::ranges::RangeMap;
+    /// let mut first = RangeMap::new();
+    /// first.insert(0..10, 100);
+    /// let mut second = RangeMap::new();
+    /// second.insert(105..110, 0);
+    /// let both = first.compose(&second);
+    /// assert_eq!(both.apply(7), 2);
+    /// assert_eq!(both.apply(3), 103);
+    /// assert_eq!(both.apply(107), 2);
+    /// ```
+    #[must_use]
+    pub fn compose(&self, then: &RangeMap<T>) -> RangeMap<T> {
+        let mut segments = vec![];
+
+        // Integers covered by `self`: follow their image through `then`.
+        for segment in &self.segments {
+            let image = segment.apply(segment.source.start)..segment.apply(segment.source.end);
+            for (piece, then_segment) in then.pieces(image) {
+                let source = piece.start - segment.destination + segment.source.start
+                    ..piece.end - segment.destination + segment.source.start;
+                let destination = then_segment.map_or(piece.start, |s| s.apply(piece.start));
+                segments.push(Segment {
+                    source,
+                    destination,
+                });
+            }
+        }
+
+        // Integers not covered by `self` stay the same, so only `then` applies to them.
+        for then_segment in &then.segments {
+            for (piece, segment) in self.pieces(then_segment.source.clone()) {
+                if segment.is_none() {
+                    segments.push(Segment {
+                        destination: then_segment.apply(piece.start),
+                        source: piece,
+                    });
+                }
+            }
+        }
+
+        segments.retain(|s| s.destination != s.source.start);
+        segments.sort_unstable_by_key(|s| s.source.start);
+
+        RangeMap { segments }
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{IntervalSet, RangeMap};
+
+    #[test]
+    fn normalizes_interval_sets() {
+        let set = IntervalSet::from_ranges([10..12, 0..2, 2..4, 1..3, 7..7]);
+        assert_eq!(set.ranges(), [0..4, 10..12]);
+        assert_eq!(set.len(), 6);
+        assert_eq!(set.min(), Some(0));
+        assert!(set.contains(3));
+        assert!(!set.contains(4));
+        assert!(set.contains(11));
+        assert!(IntervalSet::<u64>::new().is_empty());
+    }
+
+    #[test]
+    fn combines_interval_sets() {
+        let a = IntervalSet::from_ranges([0..10, 20..30]);
+        let b = IntervalSet::from_ranges([5..25, 28..29, 40..50]);
+
+        assert_eq!(a.union(&b).ranges(), [0..30, 40..50]);
+        assert_eq!(a.intersection(&b).ranges(), [5..10, 20..25, 28..29]);
+        assert_eq!(a.difference(&b).ranges(), [0..5, 25..28, 29..30]);
+        assert_eq!(b.difference(&a).ranges(), [10..20, 40..50]);
+
+        let mut c = a.clone();
+        c.insert(10..20);
+        assert_eq!(c.ranges(), [0..30]);
+    }
+
+    fn get_mock_map() -> RangeMap<i64> {
+        let mut map = RangeMap::new();
+        map.insert(98..100, 50);
+        map.insert(50..98, 52);
+        map
+    }
+
+    #[test]
+    fn maps_values_and_ranges() {
+        let map = get_mock_map();
+        assert_eq!(map.apply(49), 49);
+        assert_eq!(map.apply(50), 52);
+        assert_eq!(map.apply(99), 51);
+        assert_eq!(map.apply(100), 100);
+
+        assert_eq!(map.apply_range(40..60).ranges(), [40..50, 52..62]);
+        assert_eq!(map.apply_range(95..105).ranges(), [50..52, 97..105]);
+        let set = IntervalSet::from_ranges([0..1, 96..101]);
+        assert_eq!(map.apply_set(&set).ranges(), [0..1, 50..52, 98..101]);
+    }
+
+    #[test]
+    fn finds_preimages() {
+        let map = get_mock_map();
+        let set = IntervalSet::from_ranges([45..55, 99..101]);
+        let preimage = map.preimage(&set);
+        assert_eq!(preimage.ranges(), [45..53, 97..101]);
+        for value in 0..120 {
+            assert_eq!(
+                preimage.contains(value),
+                set.contains(map.apply(value)),
+                "{value}"
+            );
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "must not overlap")]
+    fn rejects_overlapping_segments() {
+        let mut map = get_mock_map();
+        map.insert(90..110, 0);
+    }
+
+    #[test]
+    fn reports_overlapping_segments() {
+        let mut map = get_mock_map();
+        assert_eq!(map.try_insert(99..110, 0), Err(98..100));
+        assert_eq!(map.try_insert(40..51, 0), Err(50..98));
+        assert_eq!(map, get_mock_map());
+        assert_eq!(map.try_insert(100..110, 0), Ok(()));
+        assert_eq!(map.apply(105), 5);
+    }
+
+    #[test]
+    fn composes_maps() {
+        let first = get_mock_map();
+        let mut second = RangeMap::new();
+        second.insert(0..60, 1000);
+
+        let both = first.compose(&second);
+        for value in 0..120 {
+            assert_eq!(
+                both.apply(value),
+                second.apply(first.apply(value)),
+                "{value}"
+            );
+        }
+        let set = IntervalSet::from_ranges([45..101]);
+        assert_eq!(
+            both.apply_set(&set),
+            second.apply_set(&first.apply_set(&set))
+        );
+    }
+}
@@ -0,0 +1,249 @@
+/// Module that provides the runtime support for the [`scan!`](crate::scan!) macro.
+use std::any::type_name;
+use std::error::Error;
+use std::fmt::Display;
+use std::str::FromStr;
+
+/// An error which can be returned by [`scan!`](crate::scan!).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ScanError {
+    /// The input does not have the shape of the pattern.
+    Mismatch {
+        pattern: &'static str,
+        input: String,
+    },
+    /// A captured value could not be parsed into the requested type.
+    Value {
+        value: String,
+        type_name: &'static str,
+    },
+}
+
+impl Error for ScanError {}
+
+impl Display for ScanError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ScanError::Mismatch { pattern, input } => {
+                write!(f, "\"{input}\" does not match \"{pattern}\"")
+            }
+            ScanError::Value { value, type_name } => {
+                write!(f, "could not parse \"{value}\" as {type_name}")
+            }
+        }
+    }
+}
+
+/// Counts the `{}` placeholders of a pattern, so that `scan!` can check them against its types at compile time.
+#[doc(hidden)]
+#[must_use]
+pub const fn count_placeholders(pattern: &str) -> usize {
+    let bytes = pattern.as_bytes();
+    let mut count = 0;
+    let mut i = 0;
+    while i + 1 < bytes.len() {
+        if bytes[i] == b'{' && bytes[i + 1] == b'}' {
+            count += 1;
+            i += 2;
+        } else {
+            i += 1;
+        }
+    }
+    count
+}
+
+/// Used by `scan!` to count its types in a const context.
+#[doc(hidden)]
+pub const fn unit<T: ?Sized>() {}
+
+/// Matches `input` against `pattern` and returns the trimmed text of every `{}` placeholder.
+///
+/// A placeholder extends up to the first occurrence of the literal text that follows it, or to the end
+/// of the input for a trailing placeholder. The literal text after the last placeholder has to end the
+/// input. Placeholders that directly follow each other are split at whitespace.
+#[doc(hidden)]
+pub fn captures<'a>(pattern: &'static str, input: &'a str) -> Result<Vec<&'a str>, ScanError> {
+    let mismatch = || ScanError::Mismatch {
+        pattern,
+        input: input.to_string(),
+    };
+
+    let mut literals = pattern.split("{}");
+    let mut rest = input
+        .strip_prefix(literals.next().unwrap_or_default())
+        .ok_or_else(mismatch)?;
+    let mut captures = vec![];
+
+    let mut literals = literals.peekable();
+    while let Some(literal) = literals.next() {
+        let is_last = literals.peek().is_none();
+        let end = if literal.is_empty() {
+            if is_last {
+                rest.len()
+            } else {
+                let trimmed = rest.len() - rest.trim_start().len();
+                rest[trimmed..]
+                    .find(char::is_whitespace)
+                    .map_or(rest.len(), |i| trimmed + i)
+            }
+        } else if is_last {
+            rest.strip_suffix(literal).ok_or_else(mismatch)?.len()
+        } else {
+            rest.find(literal).ok_or_else(mismatch)?
+        };
+
+        captures.push(rest[..end].trim());
+        rest = &rest[end + literal.len()..];
+    }
+
+    if rest.is_empty() {
+        Ok(captures)
+    } else {
+        Err(mismatch())
+    }
+}
+
+/// Parses a captured value, see [`scan!`](crate::scan!).
+#[doc(hidden)]
+pub fn value<T: FromStr>(capture: &str) -> Result<T, ScanError> {
+    capture.parse().map_err(|_| ScanError::Value {
+        value: capture.to_string(),
+        type_name: type_name::<T>(),
+    })
+}
+
+/// Splits a captured list at `separator`, see [`scan!`](crate::scan!). An empty capture is an empty list.
+#[doc(hidden)]
+pub fn items<'a>(capture: &'a str, separator: &'a str) -> impl Iterator<Item = &'a str> {
+    capture
+        .split(separator)
+        .map(str::trim)
+        .filter(move |_| !capture.trim().is_empty())
+}
+
+/// Parses a line according to a format string and returns its `{}` placeholders as a typed tuple.
+///
+/// Every placeholder takes one of:
+/// - a type that implements [`FromStr`](std::str::FromStr), e.g. `u32` or `String`,
+/// - `str`, which borrows the captured text as a `&str` without allocating,
+/// - `[T; "sep"]`, a list of `T`s separated by `"sep"`, returned as a `Vec`,
+/// - `("pattern" => T, ...)`, a nested pattern, which is most useful as the element of a list.
+///
+/// Captured text is trimmed. The number of placeholders is checked against the number of types at
+/// compile time, mismatching input and unparsable values return a [`ScanError`].
+///
+/// ```
+/// # use advent_of_code::scan;
+/// let (node, left, right) = scan!("{} = ({}, {})", "AAA = (BBB, CCC)" => str, str, str).unwrap();
+/// assert_eq!((node, left, right), ("AAA", "BBB", "CCC"));
+///
+/// let (game, draws) = scan!(
+///     "Game {}: {}", "Game 12: 3 blue, 4 red; 1 red" => u32, [[("{} {}" => u32, str); ","]; ";"]
+/// )
+/// .unwrap();
+/// assert_eq!(game, 12);
+/// assert_eq!(draws, [vec![(3, "blue"), (4, "red")], vec![(1, "red")]]);
+/// ```
+#[macro_export]
+macro_rules! scan {
+    (@unit $item:tt) => {
+        $crate::scan::unit::<()>()
+    };
+
+    (@parse $capture:expr, str) => {
+        Ok::<&str, $crate::scan::ScanError>($capture)
+    };
+    (@parse $capture:expr, [$item:tt; $separator:literal]) => {
+        $crate::scan::items($capture, $separator)
+            .map(|capture| $crate::scan!(@parse capture, $item))
+            .collect::<Result<Vec<_>, $crate::scan::ScanError>>()
+    };
+    (@parse $capture:expr, ($pattern:literal => $($item:tt),+ $(,)?)) => {
+        $crate::scan!($pattern, $capture => $($item),+)
+    };
+    (@parse $capture:expr, $type:ty) => {
+        $crate::scan::value::<$type>($capture)
+    };
+
+    ($pattern:literal, $input:expr => $($item:tt),+ $(,)?) => {{
+        const _ASSERT: () = assert!(
+            $crate::scan::count_placeholders($pattern) == [$($crate::scan!(@unit $item)),+].len(),
+            "{}",
+            concat!(
+                "the number of `{}` placeholders in \"",
+                $pattern,
+                "\" does not match the number of types"
+            ),
+        );
+        $crate::scan::captures($pattern, $input).and_then(|captures| {
+            let mut captures = captures.into_iter();
+            Ok(($($crate::scan!(@parse captures.next().unwrap_or_default(), $item)?,)+))
+        })
+    }};
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{count_placeholders, ScanError};
+
+    #[test]
+    fn counts_placeholders() {
+        assert_eq!(count_placeholders("{} = ({}, {})"), 3);
+        assert_eq!(count_placeholders("no placeholders {"), 0);
+    }
+
+    #[test]
+    fn scans_typed_values() {
+        let result =
+            crate::scan!("x={}, y={}, name={}", "x=-3, y=14, name= probe " => i32, u8, String);
+        assert_eq!(result, Ok((-3, 14, "probe".to_string())));
+
+        let (a, b) = crate::scan!("{}{}", "move  7" => str, u32).unwrap();
+        assert_eq!((a, b), ("move", 7));
+    }
+
+    #[test]
+    fn scans_lists() {
+        let (name, values) = crate::scan!("{}: {}", "list: 1, 2, 3" => str, [u64; ","]).unwrap();
+        assert_eq!(name, "list");
+        assert_eq!(values, [1, 2, 3]);
+
+        let (values,) = crate::scan!("[{}]", "[]" => [u64; ","]).unwrap();
+        assert!(values.is_empty());
+
+        let (pairs,) = crate::scan!("{}", "a=1; b=2" => [("{}={}" => char, u8); ";"]).unwrap();
+        assert_eq!(pairs, [('a', 1), ('b', 2)]);
+    }
+
+    #[test]
+    fn anchors_trailing_literals() {
+        let (text,) = crate::scan!("{})", "a) b)" => str).unwrap();
+        assert_eq!(text, "a) b");
+
+        let (name, values) = crate::scan!("{} = ({})", "f = (g(1), 2)" => str, String).unwrap();
+        assert_eq!((name, values.as_str()), ("f", "g(1), 2"));
+    }
+
+    #[test]
+    fn reports_errors() {
+        assert_eq!(
+            crate::scan!("{} = ({}, {})", "AAA = BBB" => str, str, str),
+            Err(ScanError::Mismatch {
+                pattern: "{} = ({}, {})",
+                input: "AAA = BBB".into()
+            })
+        );
+        assert_eq!(
+            crate::scan!("({})", "(1) trailing" => u8),
+            Err(ScanError::Mismatch {
+                pattern: "({})",
+                input: "(1) trailing".into()
+            })
+        );
+
+        let error = crate::scan!("{}, {}", "1, x" => u8, u8).unwrap_err();
+        assert_eq!(error.to_string(), "could not parse \"x\" as u8");
+    }
+}
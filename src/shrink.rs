@@ -0,0 +1,127 @@
+/// Removes as much of `input` as possible while `fails` keeps returning `true` for it, with delta
+/// debugging. Used by `cargo shrink` to minimise failing inputs.
+///
+/// Removes whole sections (separated by blank lines) first, then lines, then whitespace-separated
+/// tokens of each line, and starts over until nothing more can be removed.
+/// `fails` should return `true` for `input` itself.
+///
+/// ```
+/// # use advent_of_code::shrink::shrink;
+/// let input = "1 2 3\n4 5 6\n7 8 9\n";
+/// let shrunk = shrink(input, |candidate| candidate.contains('5'));
+/// assert_eq!(shrunk, "5\n");
+/// ```
+pub fn shrink(input: &str, mut fails: impl FnMut(&str) -> bool) -> String {
+    let mut current = input.to_string();
+
+    loop {
+        let before = current.len();
+
+        let sections: Vec<&str> = current.split_inclusive("\n\n").collect();
+        current = ddmin(&sections, |units| fails(&units.concat())).concat();
+
+        let lines: Vec<&str> = current.split_inclusive('\n').collect();
+        current = ddmin(&lines, |units| fails(&units.concat())).concat();
+
+        current = shrink_tokens(&current, &mut fails);
+
+        if current.len() == before {
+            return current;
+        }
+    }
+}
+
+/// Removes tokens from each line in turn, keeping the rest of the input as is.
+fn shrink_tokens(input: &str, fails: &mut impl FnMut(&str) -> bool) -> String {
+    let mut lines: Vec<String> = input.split_inclusive('\n').map(String::from).collect();
+
+    for i in 0..lines.len() {
+        let line = lines[i].clone();
+        let (text, newline) = match line.strip_suffix('\n') {
+            Some(text) => (text, "\n"),
+            None => (line.as_str(), ""),
+        };
+        let tokens: Vec<&str> = text.split_inclusive(' ').collect();
+        if tokens.len() < 2 {
+            continue;
+        }
+
+        let join = |tokens: &[&str]| format!("{}{newline}", tokens.concat().trim_end_matches(' '));
+        let kept = ddmin(&tokens, |tokens| {
+            let mut candidate = lines.clone();
+            candidate[i] = join(tokens);
+            fails(&candidate.concat())
+        });
+        lines[i] = join(&kept);
+    }
+
+    lines.concat()
+}
+
+/// Zeller's ddmin: finds a subsequence of `units` for which `fails` still holds and from which no
+/// single unit can be removed. Only tries removing chunks, which at the first split are the halves.
+fn ddmin<'a>(units: &[&'a str], mut fails: impl FnMut(&[&'a str]) -> bool) -> Vec<&'a str> {
+    let mut units = units.to_vec();
+    let mut chunks = 2;
+
+    while !units.is_empty() {
+        let size = units.len().div_ceil(chunks);
+        let mut reduced = false;
+
+        for start in (0..units.len()).step_by(size) {
+            let end = (start + size).min(units.len());
+            let complement = [&units[..start], &units[end..]].concat();
+            if fails(&complement) {
+                units = complement;
+                chunks = (chunks - 1).max(2);
+                reduced = true;
+                break;
+            }
+        }
+
+        if !reduced {
+            if size == 1 {
+                break;
+            }
+            chunks = (chunks * 2).min(units.len());
+        }
+    }
+
+    units
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{ddmin, shrink};
+
+    #[test]
+    fn finds_minimal_subsequences() {
+        let units: Vec<String> = (0..100).map(|i| i.to_string()).collect();
+        let units: Vec<&str> = units.iter().map(String::as_str).collect();
+        let mut tests = 0;
+        let kept = ddmin(&units, |units| {
+            tests += 1;
+            units.contains(&"13") && units.contains(&"42")
+        });
+        assert_eq!(kept, ["13", "42"]);
+        assert!(tests < 100, "took {tests} tests");
+
+        assert_eq!(ddmin(&units, |_| true), Vec::<&str>::new());
+    }
+
+    #[test]
+    fn shrinks_sections_lines_and_tokens() {
+        let input = "seeds: 1 2 3\n\na:\n1 2\n3 4\n\nb:\n5 6\n7 8\n";
+        let shrunk = shrink(input, |candidate| {
+            candidate.starts_with("seeds:") && candidate.contains("7 8")
+        });
+        assert_eq!(shrunk, "seeds:\n7 8\n");
+
+        // A failure that depends on the number of lines keeps them, but empties them.
+        let input = "a b\nc d\ne f\n";
+        let shrunk = shrink(input, |candidate| candidate.lines().count() == 3);
+        assert_eq!(shrunk, "\n\n\n");
+    }
+}
@@ -0,0 +1,299 @@
+/// Module that styles terminal output. Styles are only written when stdout is a terminal and
+/// `NO_COLOR` is not set, which `--color=always|never` overrides.
+use std::env;
+use std::ffi::OsString;
+use std::fmt::Display;
+use std::io::{stdout, IsTerminal};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+const RESET: &str = "\x1b[0m";
+
+/// A terminal colour, e.g. for highlighting cells with [`Grid::display_with`](crate::grid::Grid::display_with).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Colour {
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+}
+
+impl Colour {
+    fn ansi_code(self) -> &'static str {
+        match self {
+            Colour::Red => "31",
+            Colour::Green => "32",
+            Colour::Yellow => "33",
+            Colour::Blue => "34",
+            Colour::Magenta => "35",
+            Colour::Cyan => "36",
+        }
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// The value of the `--color` option.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ColorChoice {
+    /// Style output if stdout is a terminal and `NO_COLOR` is not set.
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+impl FromStr for ColorChoice {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "auto" => Ok(ColorChoice::Auto),
+            "always" => Ok(ColorChoice::Always),
+            "never" => Ok(ColorChoice::Never),
+            _ => Err(format!(
+                "unknown color choice \"{s}\", expecting auto, always or never."
+            )),
+        }
+    }
+}
+
+impl Display for ColorChoice {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            ColorChoice::Auto => "auto",
+            ColorChoice::Always => "always",
+            ColorChoice::Never => "never",
+        })
+    }
+}
+
+impl ColorChoice {
+    /// Reads `--color=<choice>` or `--color <choice>` from the command line.
+    fn from_args() -> Self {
+        let args: Vec<String> = env::args().collect();
+        let value =
+            args.iter()
+                .enumerate()
+                .find_map(|(i, arg)| match arg.strip_prefix("--color") {
+                    Some("") => args.get(i + 1).map(String::as_str),
+                    Some(value) => value.strip_prefix('='),
+                    None => None,
+                });
+
+        value.and_then(|v| v.parse().ok()).unwrap_or_default()
+    }
+
+    /// Decides whether to style output. `NO_COLOR` only counts if it is not empty, see <https://no-color.org>.
+    #[must_use]
+    pub fn resolve(self, no_color: Option<OsString>, is_terminal: bool) -> bool {
+        match self {
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+            ColorChoice::Auto => is_terminal && no_color.is_none_or(|v| v.is_empty()),
+        }
+    }
+}
+
+const UNRESOLVED: u8 = 0;
+const ENABLED: u8 = 1;
+const DISABLED: u8 = 2;
+
+static STATE: AtomicU8 = AtomicU8::new(UNRESOLVED);
+
+/// Overrides the choice read from the command line, e.g. after parsing it with pico-args.
+pub fn set_color_choice(choice: ColorChoice) {
+    let enabled = choice.resolve(env::var_os("NO_COLOR"), stdout().is_terminal());
+    STATE.store(if enabled { ENABLED } else { DISABLED }, Ordering::Relaxed);
+}
+
+/// Returns whether output is styled. Resolved from `--color`, `NO_COLOR` and stdout on first use.
+#[must_use]
+pub fn enabled() -> bool {
+    if STATE.load(Ordering::Relaxed) == UNRESOLVED {
+        set_color_choice(ColorChoice::from_args());
+    }
+    STATE.load(Ordering::Relaxed) == ENABLED
+}
+
+/// The `--color` argument that makes a child process style its output like this one,
+/// even if its stdout is piped to us.
+#[must_use]
+pub fn child_arg() -> String {
+    let choice = if enabled() {
+        ColorChoice::Always
+    } else {
+        ColorChoice::Never
+    };
+    format!("--color={choice}")
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// A combination of text attributes and a colour.
+///
+/// ```
+/// # use advent_of_code::style::{Colour, Style};
+/// let warning = Style::new().bold().fg(Colour::Yellow);
+/// println!("{}", warning.paint("careful"));
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Style {
+    colour: Option<Colour>,
+    bold: bool,
+    italic: bool,
+}
+
+impl Style {
+    #[must_use]
+    pub const fn new() -> Self {
+        Style {
+            colour: None,
+            bold: false,
+            italic: false,
+        }
+    }
+
+    #[must_use]
+    pub const fn bold(self) -> Self {
+        Style { bold: true, ..self }
+    }
+
+    #[must_use]
+    pub const fn italic(self) -> Self {
+        Style {
+            italic: true,
+            ..self
+        }
+    }
+
+    #[must_use]
+    pub const fn fg(self, colour: Colour) -> Self {
+        Style {
+            colour: Some(colour),
+            ..self
+        }
+    }
+
+    /// Wraps `value` so that it displays in this style.
+    pub fn paint<T: Display>(self, value: T) -> Painted<T> {
+        Painted { style: self, value }
+    }
+
+    fn is_plain(self) -> bool {
+        self == Style::new()
+    }
+
+    fn escape(self) -> String {
+        let codes: Vec<&str> = [
+            self.bold.then_some("1"),
+            self.italic.then_some("3"),
+            self.colour.map(Colour::ansi_code),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+        format!("\x1b[{}m", codes.join(";"))
+    }
+}
+
+impl From<Colour> for Style {
+    fn from(colour: Colour) -> Self {
+        Style::new().fg(colour)
+    }
+}
+
+/// A value that displays in a [`Style`], see [`Style::paint`].
+#[derive(Debug, Clone, Copy)]
+pub struct Painted<T> {
+    style: Style,
+    value: T,
+}
+
+impl<T: Display> Display for Painted<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.style.is_plain() || !enabled() {
+            self.value.fmt(f)
+        } else {
+            write!(f, "{}", self.style.escape())?;
+            self.value.fmt(f)?;
+            f.write_str(RESET)
+        }
+    }
+}
+
+pub fn bold<T: Display>(value: T) -> Painted<T> {
+    Style::new().bold().paint(value)
+}
+
+pub fn italic<T: Display>(value: T) -> Painted<T> {
+    Style::new().italic().paint(value)
+}
+
+pub fn coloured<T: Display>(value: T, colour: Colour) -> Painted<T> {
+    Style::from(colour).paint(value)
+}
+
+/// Removes ANSI escape sequences, e.g. from the output of a child process.
+#[must_use]
+pub fn strip(text: &str) -> String {
+    let mut stripped = String::with_capacity(text.len());
+    let mut chars = text.chars();
+
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            // skip the control sequence up to and including its final byte.
+            for c in chars.by_ref() {
+                if c.is_ascii_alphabetic() {
+                    break;
+                }
+            }
+        } else {
+            stripped.push(c);
+        }
+    }
+
+    stripped
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{bold, set_color_choice, strip, ColorChoice, Colour, Style};
+    use std::ffi::OsString;
+
+    #[test]
+    fn resolves_color_choice() {
+        let no_color = || Some(OsString::from("1"));
+        assert!(ColorChoice::Auto.resolve(None, true));
+        assert!(!ColorChoice::Auto.resolve(None, false));
+        assert!(!ColorChoice::Auto.resolve(no_color(), true));
+        assert!(ColorChoice::Auto.resolve(Some(OsString::new()), true));
+        assert!(ColorChoice::Always.resolve(no_color(), false));
+        assert!(!ColorChoice::Never.resolve(None, true));
+
+        assert_eq!("never".parse(), Ok(ColorChoice::Never));
+        assert!("sometimes".parse::<ColorChoice>().is_err());
+    }
+
+    #[test]
+    fn paints_values() {
+        // Tests only ever force styling on, so that they can run in parallel.
+        set_color_choice(ColorChoice::Always);
+        assert_eq!(bold(42).to_string(), "\x1b[1m42\x1b[0m");
+        let style = Style::new().italic().fg(Colour::Cyan);
+        assert_eq!(style.paint("x").to_string(), "\x1b[3;36mx\x1b[0m");
+        assert_eq!(Style::new().paint("plain").to_string(), "plain");
+    }
+
+    #[test]
+    fn strips_escapes() {
+        assert_eq!(
+            strip("Part 1: \x1b[1m42\x1b[0m (1.0ms)\x1b[2J"),
+            "Part 1: 42 (1.0ms)"
+        );
+    }
+}
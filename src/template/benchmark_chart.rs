@@ -0,0 +1,304 @@
+/// Module that renders benchmark timings as bar charts on a logarithmic scale.
+/// Charts are emitted as an SVG document (embedded into the readme) and as plain text for the terminal.
+use std::cmp;
+use std::fmt::Write;
+
+use crate::template::readme_benchmarks::Timings;
+
+const PART_COLORS: [&str; 2] = ["#4c8eda", "#f2a541"];
+
+const SVG_WIDTH: f64 = 720.0;
+const SVG_LABEL_WIDTH: f64 = 70.0;
+const SVG_MARGIN: f64 = 20.0;
+const SVG_AXIS_HEIGHT: f64 = 40.0;
+const SVG_BAR_HEIGHT: f64 = 10.0;
+const SVG_ROW_GAP: f64 = 8.0;
+
+/// A logarithmic axis spanning whole powers of ten.
+struct LogScale {
+    min_exp: i32,
+    max_exp: i32,
+}
+
+impl LogScale {
+    fn from_timings(timings: &[Timings]) -> Option<Self> {
+        let values: Vec<f64> = timings
+            .iter()
+            .flat_map(|t| [t.part_1_nanos, t.part_2_nanos])
+            .flatten()
+            .filter(|x| *x > 0.0)
+            .collect();
+
+        let min = values.iter().copied().reduce(f64::min)?;
+        let max = values.iter().copied().reduce(f64::max)?;
+
+        #[allow(clippy::cast_possible_truncation)]
+        let min_exp = min.log10().floor() as i32;
+        #[allow(clippy::cast_possible_truncation)]
+        let max_exp = cmp::max(max.log10().ceil() as i32, min_exp + 1);
+
+        Some(Self { min_exp, max_exp })
+    }
+
+    /// Position of `nanos` on the axis, in the range `0.0..=1.0`.
+    fn position(&self, nanos: f64) -> f64 {
+        if nanos <= 0.0 {
+            return 0.0;
+        }
+        let span = f64::from(self.max_exp - self.min_exp);
+        ((nanos.log10() - f64::from(self.min_exp)) / span).clamp(0.0, 1.0)
+    }
+
+    fn ticks(&self) -> impl Iterator<Item = i32> {
+        self.min_exp..=self.max_exp
+    }
+}
+
+/// Formats a power of ten (in nanoseconds) as a human readable duration, e.g. `100µs`.
+fn format_tick(exp: i32) -> String {
+    let (unit, base) = match exp {
+        e if e < 3 => ("ns", 0),
+        e if e < 6 => ("µs", 3),
+        e if e < 9 => ("ms", 6),
+        _ => ("s", 9),
+    };
+    format!("{}{unit}", 10_u64.pow((exp - base).unsigned_abs()))
+}
+
+fn escape_xml(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+/// Renders a horizontal bar chart of the part 1 / part 2 timings of every day as an SVG document.
+/// Returns [`None`] if there are no timings to plot.
+#[must_use]
+pub fn render_svg(timings: &[Timings]) -> Option<String> {
+    let scale = LogScale::from_timings(timings)?;
+
+    let plot_x = SVG_MARGIN + SVG_LABEL_WIDTH;
+    let plot_width = SVG_WIDTH - plot_x - SVG_MARGIN;
+    let row_height = 2.0 * SVG_BAR_HEIGHT + SVG_ROW_GAP;
+    #[allow(clippy::cast_precision_loss)]
+    let plot_height = row_height * timings.len() as f64;
+    let height = SVG_MARGIN * 2.0 + plot_height + SVG_AXIS_HEIGHT;
+
+    let mut svg = String::new();
+
+    let _ = writeln!(
+        svg,
+        r##"<svg xmlns="http://www.w3.org/2000/svg" width="{SVG_WIDTH}" height="{height}" viewBox="0 0 {SVG_WIDTH} {height}" font-family="sans-serif" font-size="11">"##
+    );
+    let _ = writeln!(
+        svg,
+        r##"<rect width="100%" height="100%" fill="#ffffff"/>"##
+    );
+
+    for exp in scale.ticks() {
+        let x = plot_x + scale.position(10_f64.powi(exp)) * plot_width;
+        let _ = writeln!(
+            svg,
+            r##"<line x1="{x:.1}" y1="{SVG_MARGIN}" x2="{x:.1}" y2="{:.1}" stroke="#dddddd"/>"##,
+            SVG_MARGIN + plot_height
+        );
+        let _ = writeln!(
+            svg,
+            r##"<text x="{x:.1}" y="{:.1}" text-anchor="middle" fill="#555555">{}</text>"##,
+            SVG_MARGIN + plot_height + 16.0,
+            format_tick(exp)
+        );
+    }
+
+    for (row, timing) in timings.iter().enumerate() {
+        #[allow(clippy::cast_precision_loss)]
+        let y = SVG_MARGIN + row_height * row as f64;
+
+        let _ = writeln!(
+            svg,
+            r##"<text x="{SVG_MARGIN}" y="{:.1}" dominant-baseline="middle">Day {}</text>"##,
+            y + SVG_BAR_HEIGHT,
+            timing.day
+        );
+
+        let parts = [
+            (&timing.part_1, timing.part_1_nanos),
+            (&timing.part_2, timing.part_2_nanos),
+        ];
+
+        for (index, (label, nanos)) in parts.into_iter().enumerate() {
+            let Some(nanos) = nanos else {
+                continue;
+            };
+            #[allow(clippy::cast_precision_loss)]
+            let bar_y = y + SVG_BAR_HEIGHT * index as f64;
+            let bar_width = (scale.position(nanos) * plot_width).max(1.0);
+            let _ = writeln!(
+                svg,
+                r##"<rect x="{plot_x:.1}" y="{bar_y:.1}" width="{bar_width:.1}" height="{:.1}" fill="{}"><title>Day {} part {}: {}</title></rect>"##,
+                SVG_BAR_HEIGHT - 1.0,
+                PART_COLORS[index],
+                timing.day,
+                index + 1,
+                escape_xml(label.as_deref().unwrap_or("-"))
+            );
+        }
+    }
+
+    let legend_y = SVG_MARGIN + plot_height + 32.0;
+    for (index, color) in PART_COLORS.iter().enumerate() {
+        #[allow(clippy::cast_precision_loss)]
+        let x = plot_x + 80.0 * index as f64;
+        let _ = writeln!(
+            svg,
+            r##"<rect x="{x:.1}" y="{:.1}" width="10" height="10" fill="{color}"/><text x="{:.1}" y="{legend_y:.1}">Part {}</text>"##,
+            legend_y - 9.0,
+            x + 14.0,
+            index + 1
+        );
+    }
+
+    svg.push_str("</svg>\n");
+    Some(svg)
+}
+
+/// Renders a horizontal bar chart of the part 1 / part 2 timings of every day as plain text.
+/// `width` is the number of characters used for the bars. Returns [`None`] if there are no timings to plot.
+#[must_use]
+pub fn render_ascii(timings: &[Timings], width: usize) -> Option<String> {
+    let scale = LogScale::from_timings(timings)?;
+    let mut lines: Vec<String> = vec![];
+
+    for timing in timings {
+        let parts = [
+            (&timing.part_1, timing.part_1_nanos),
+            (&timing.part_2, timing.part_2_nanos),
+        ];
+
+        for (index, (label, nanos)) in parts.into_iter().enumerate() {
+            let prefix = if index == 0 {
+                format!("Day {}", timing.day)
+            } else {
+                String::new()
+            };
+
+            let bar = match nanos {
+                #[allow(
+                    clippy::cast_possible_truncation,
+                    clippy::cast_sign_loss,
+                    clippy::cast_precision_loss
+                )]
+                Some(nanos) => {
+                    let len = ((scale.position(nanos) * width as f64).round() as usize).max(1);
+                    "█".repeat(len)
+                }
+                None => String::new(),
+            };
+
+            lines.push(format!(
+                "{prefix:<6} P{} │{bar:<width$}│ {}",
+                index + 1,
+                label.as_deref().unwrap_or("-")
+            ));
+        }
+    }
+
+    let mut axis: Vec<char> = vec![' '; width + 1];
+    let mut tick_labels: Vec<char> = vec![' '; width + 8];
+    for exp in scale.ticks() {
+        #[allow(
+            clippy::cast_possible_truncation,
+            clippy::cast_sign_loss,
+            clippy::cast_precision_loss
+        )]
+        let pos = (scale.position(10_f64.powi(exp)) * width as f64).round() as usize;
+        axis[pos] = '┴';
+        for (offset, c) in format_tick(exp).chars().enumerate() {
+            if let Some(slot) = tick_labels.get_mut(pos + offset) {
+                *slot = c;
+            }
+        }
+    }
+
+    lines.push(format!(
+        "{:<9} {}",
+        "",
+        axis.into_iter().collect::<String>()
+    ));
+    lines.push(format!(
+        "{:<9} {}",
+        "",
+        tick_labels.into_iter().collect::<String>().trim_end()
+    ));
+
+    Some(lines.join("\n"))
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{format_tick, render_ascii, render_svg};
+    use crate::day;
+    use crate::template::readme_benchmarks::Timings;
+
+    fn get_mock_timings() -> Vec<Timings> {
+        vec![
+            Timings {
+                day: day!(1),
+                part_1: Some("100.0ns".into()),
+                part_2: Some("10.0µs".into()),
+                part_1_nanos: Some(100.0),
+                part_2_nanos: Some(10_000.0),
+                total_nanos: 10_100.0,
+            },
+            Timings {
+                day: day!(2),
+                part_1: Some("1.0ms".into()),
+                part_2: None,
+                part_1_nanos: Some(1_000_000.0),
+                part_2_nanos: None,
+                total_nanos: 1_000_000.0,
+            },
+        ]
+    }
+
+    #[test]
+    fn formats_ticks() {
+        assert_eq!(format_tick(0), "1ns");
+        assert_eq!(format_tick(2), "100ns");
+        assert_eq!(format_tick(4), "10µs");
+        assert_eq!(format_tick(6), "1ms");
+        assert_eq!(format_tick(10), "10s");
+    }
+
+    #[test]
+    fn renders_nothing_without_timings() {
+        assert!(render_svg(&[]).is_none());
+        assert!(render_ascii(&[], 40).is_none());
+    }
+
+    #[test]
+    fn renders_svg_bars() {
+        let svg = render_svg(&get_mock_timings()).unwrap();
+        assert!(svg.starts_with("<svg"));
+        assert!(svg.trim_end().ends_with("</svg>"));
+        assert_eq!(svg.matches("<title>").count(), 3);
+        assert!(svg.contains("Day 01 part 2: 10.0µs"));
+        assert!(svg.contains(">100ns</text>"));
+        assert!(svg.contains(">1ms</text>"));
+    }
+
+    #[test]
+    fn renders_ascii_bars() {
+        let chart = render_ascii(&get_mock_timings(), 40).unwrap();
+        let lines: Vec<&str> = chart.lines().collect();
+        assert_eq!(lines.len(), 6);
+        assert!(lines[0].starts_with("Day 01 P1 │"));
+        assert!(lines[3].ends_with("│ -"));
+
+        let bar_len = |line: &str| line.matches('█').count();
+        assert!(bar_len(lines[0]) < bar_len(lines[1]));
+        assert!(bar_len(lines[1]) < bar_len(lines[2]));
+        assert_eq!(bar_len(lines[2]), 40);
+        assert_eq!(bar_len(lines[3]), 0);
+    }
+}
@@ -1,59 +1,114 @@
-use std::io;
+use std::time::Duration;
 
+use crate::style::{bold, italic};
 use crate::template::{
-    readme_benchmarks::{self, Timings},
-    ANSI_BOLD, ANSI_ITALIC, ANSI_RESET,
+    benchmark_chart, ledger,
+    readme_benchmarks::{self, SortOrder, Timings},
+    report::{self, DayReport, Format, Status},
+    Error,
 };
 use crate::{all_days, Day};
 
-pub fn handle(is_release: bool, is_timed: bool) {
+/// Width of the terminal benchmark chart, in characters.
+const CHART_WIDTH: usize = 50;
+
+/// Runs the solutions of all days. Days that crash, time out or produce a wrong answer do not stop the run.
+/// Every failure is reported when it occurs, and [`Error::Failures`] is returned once all days have been run.
+pub fn handle(
+    is_release: bool,
+    is_timed: bool,
+    sort: SortOrder,
+    report: Option<(String, Format)>,
+    timeout: Option<Duration>,
+) -> Result<(), Error> {
     let mut timings: Vec<Timings> = vec![];
+    let mut reports: Vec<DayReport> = vec![];
+    let mut failures: Vec<Error> = vec![];
 
-    all_days().for_each(|day| {
+    for day in all_days() {
         if day > 1 {
             println!();
         }
 
-        println!("{ANSI_BOLD}Day {day}{ANSI_RESET}");
+        println!("{}", bold(format!("Day {day}")));
         println!("------");
 
-        let output = child_commands::run_solution(day, is_timed, is_release).unwrap();
+        let mut output = vec![];
 
-        if output.is_empty() {
-            println!("Not solved.");
-        } else {
-            let val = child_commands::parse_exec_time(&output, day);
-            timings.push(val);
+        match child_commands::run_solution(day, is_timed, is_release, timeout, &mut output) {
+            Ok(()) => timings.push(child_commands::parse_exec_time(&output, day)),
+            Err(Error::NotScaffolded(_)) => {
+                println!("Not solved.");
+                continue;
+            }
+            // failed days keep their previous row in the benchmark table.
+            Err(e) => report_failure(&mut failures, e),
         }
-    });
+
+        let day_report = DayReport::new(
+            day,
+            child_commands::parse_answers(&output),
+            &ledger::read(day),
+        );
+
+        let wrong_parts: Vec<u8> = day_report
+            .parts
+            .iter()
+            .filter(|part| part.status == Status::Failed)
+            .map(|part| part.part)
+            .collect();
+
+        if !wrong_parts.is_empty() {
+            report_failure(
+                &mut failures,
+                Error::WrongAnswer {
+                    day,
+                    parts: wrong_parts,
+                },
+            );
+        }
+
+        reports.push(day_report);
+    }
+
+    if let Some((path, format)) = report {
+        match report::write(&path, format, &reports) {
+            Ok(()) => println!("\nWrote report to \"{path}\"."),
+            Err(e) => report_failure(&mut failures, Error::io_at(&path)(e)),
+        }
+    }
 
     if is_timed {
         let total_millis = timings.iter().map(|x| x.total_nanos).sum::<f64>() / 1_000_000_f64;
 
-        println!("\n{ANSI_BOLD}Total:{ANSI_RESET} {ANSI_ITALIC}{total_millis:.2}ms{ANSI_RESET}");
+        println!(
+            "\n{} {}",
+            bold("Total:"),
+            italic(format!("{total_millis:.2}ms"))
+        );
+
+        if let Some(chart) = benchmark_chart::render_ascii(&timings, CHART_WIDTH) {
+            println!("\n{chart}");
+        }
 
         if is_release {
-            match readme_benchmarks::update(timings, total_millis) {
+            match readme_benchmarks::update(timings, sort) {
                 Ok(()) => println!("Successfully updated README with benchmarks."),
-                Err(_) => {
-                    eprintln!("Failed to update readme with benchmarks.");
-                }
+                Err(e) => report_failure(&mut failures, e.into()),
             }
         }
     }
-}
 
-#[derive(Debug)]
-pub enum Error {
-    BrokenPipe,
-    Parser(String),
-    IO(io::Error),
+    if failures.is_empty() {
+        Ok(())
+    } else {
+        Err(Error::Failures(failures))
+    }
 }
 
-impl From<std::io::Error> for Error {
-    fn from(e: std::io::Error) -> Self {
-        Error::IO(e)
-    }
+fn report_failure(failures: &mut Vec<Error>, e: Error) {
+    eprintln!("Error: {e}");
+    failures.push(e);
 }
 
 #[must_use]
@@ -63,21 +118,34 @@ pub fn get_path_for_bin(day: Day) -> String {
 
 /// All solutions live in isolated binaries.
 /// This module encapsulates interaction with these binaries, both invoking them as well as parsing the timing output.
-mod child_commands {
-    use super::{get_path_for_bin, Error};
+pub(crate) mod child_commands {
+    use super::get_path_for_bin;
+    use crate::style;
+    use crate::template::commands::wait_for_child;
+    use crate::template::readme_benchmarks::parse_duration;
+    use crate::template::report::PartOutput;
+    use crate::template::Error;
     use crate::Day;
     use std::{
-        io::{BufRead, BufReader},
+        io::{self, BufRead, BufReader, Write},
         path::Path,
         process::{Command, Stdio},
         thread,
+        time::Duration,
     };
 
-    /// Run the solution bin for a given day
-    pub fn run_solution(day: Day, is_timed: bool, is_release: bool) -> Result<Vec<String>, Error> {
+    /// Run the solution bin for a given day, collecting its stdout lines into `output`.
+    /// `output` holds everything the solution printed even if it fails or times out.
+    pub fn run_solution(
+        day: Day,
+        is_timed: bool,
+        is_release: bool,
+        timeout: Option<Duration>,
+        output: &mut Vec<String>,
+    ) -> Result<(), Error> {
         // skip command invocation for days that have not been scaffolded yet.
         if !Path::new(&get_path_for_bin(day)).exists() {
-            return Ok(vec![]);
+            return Err(Error::NotScaffolded(day));
         }
 
         let day_padded = day.to_string();
@@ -87,42 +155,95 @@ mod child_commands {
             args.push("--release");
         }
 
+        if cfg!(feature = "embed_inputs") {
+            // build the solutions with the same inputs setup as the runner.
+            args.push("--features");
+            args.push("embed_inputs");
+        }
+
+        // the child's stdout is piped to us, so it styles its output like we do.
+        let color = style::child_arg();
+        args.push("--");
+        args.push(&color);
+
         if is_timed {
             // mirror `--time` flag to child invocations.
-            args.push("--");
             args.push("--time");
         }
 
+        let mut cmd = Command::new("cargo");
+        cmd.args(&args);
+        run_command(cmd, day, timeout, None, true, output)
+    }
+
+    /// Run `cmd` for a given day, feeding it `stdin` and collecting its stdout lines into `output`.
+    /// Stdout is forwarded to our stdout if `echo` is set, stderr is always forwarded.
+    pub fn run_command(
+        mut cmd: Command,
+        day: Day,
+        timeout: Option<Duration>,
+        stdin: Option<&str>,
+        echo: bool,
+        output: &mut Vec<String>,
+    ) -> Result<(), Error> {
         // spawn child command with piped stdout/stderr.
         // forward output to stdout/stderr while grabbing stdout lines.
 
-        let mut cmd = Command::new("cargo")
-            .args(&args)
+        let mut cmd = cmd
+            .stdin(if stdin.is_some() {
+                Stdio::piped()
+            } else {
+                Stdio::inherit()
+            })
             .stdout(Stdio::piped())
             .stderr(Stdio::piped())
             .spawn()?;
 
-        let stdout = BufReader::new(cmd.stdout.take().ok_or(super::Error::BrokenPipe)?);
-        let stderr = BufReader::new(cmd.stderr.take().ok_or(super::Error::BrokenPipe)?);
+        let broken_pipe = || Error::from(io::Error::from(io::ErrorKind::BrokenPipe));
 
-        let mut output = vec![];
+        // write from a thread, a child that does not read its stdin must not block us.
+        let stdin_thread = match (stdin, cmd.stdin.take()) {
+            (Some(text), Some(mut pipe)) => {
+                let text = text.to_string();
+                Some(thread::spawn(move || {
+                    // a child may exit without reading all of it.
+                    let _ = pipe.write_all(text.as_bytes());
+                }))
+            }
+            _ => None,
+        };
 
-        let thread = thread::spawn(move || {
-            stderr.lines().for_each(|line| {
-                eprintln!("{}", line.unwrap());
+        let stdout = BufReader::new(cmd.stdout.take().ok_or_else(broken_pipe)?);
+        let stderr = BufReader::new(cmd.stderr.take().ok_or_else(broken_pipe)?);
+
+        let stderr_thread = thread::spawn(move || {
+            stderr.lines().map_while(Result::ok).for_each(|line| {
+                eprintln!("{line}");
             });
         });
 
-        for line in stdout.lines() {
-            let line = line.unwrap();
-            println!("{line}");
-            output.push(line);
-        }
+        let stdout_thread = thread::spawn(move || {
+            stdout
+                .lines()
+                .map_while(Result::ok)
+                .inspect(|line| {
+                    if echo {
+                        println!("{line}");
+                    }
+                })
+                .collect::<Vec<String>>()
+        });
+
+        let status = wait_for_child(&mut cmd, day, timeout);
 
-        thread.join().unwrap();
-        cmd.wait()?;
+        // the pipes are closed once the child has exited, which ends all threads.
+        output.extend(stdout_thread.join().unwrap_or_default());
+        let _ = stderr_thread.join();
+        if let Some(stdin_thread) = stdin_thread {
+            let _ = stdin_thread.join();
+        }
 
-        Ok(output)
+        status
     }
 
     pub fn parse_exec_time(output: &[String], day: Day) -> super::Timings {
@@ -130,10 +251,15 @@ mod child_commands {
             day,
             part_1: None,
             part_2: None,
+            part_1_nanos: None,
+            part_2_nanos: None,
             total_nanos: 0_f64,
         };
 
-        output
+        // timings end up in the readme, so they must not contain escape codes.
+        let lines: Vec<String> = output.iter().map(|l| style::strip(l)).collect();
+
+        lines
             .iter()
             .filter_map(|l| {
                 if !l.contains(" samples)") {
@@ -151,8 +277,10 @@ mod child_commands {
             .for_each(|(part, timing_str, nanos)| {
                 if part.contains("Part 1") {
                     timings.part_1 = Some(timing_str.into());
+                    timings.part_1_nanos = Some(nanos);
                 } else if part.contains("Part 2") {
                     timings.part_2 = Some(timing_str.into());
+                    timings.part_2_nanos = Some(nanos);
                 }
 
                 timings.total_nanos += nanos;
@@ -161,27 +289,106 @@ mod child_commands {
         timings
     }
 
-    fn parse_to_float(s: &str, postfix: &str) -> Option<f64> {
-        s.split(postfix).next()?.parse().ok()
+    /// Parses a single result line, e.g. `Part 1: 42 (74.13ns @ 100 samples)`.
+    fn parse_answer(result: &str) -> PartOutput {
+        if result.starts_with('✖') {
+            return PartOutput::Unsolved;
+        }
+
+        let Some((answer, timing)) = result
+            .strip_suffix(')')
+            .and_then(|result| result.rsplit_once(" ("))
+        else {
+            return PartOutput::Answer {
+                answer: result.trim().to_string(),
+                duration: None,
+                nanos: None,
+            };
+        };
+
+        let duration = timing.split('@').next().unwrap_or_default().trim();
+
+        PartOutput::Answer {
+            answer: answer.trim().to_string(),
+            duration: Some(duration.to_string()),
+            nanos: parse_duration(duration),
+        }
+    }
+
+    /// Parses the answers a solution printed for both parts.
+    /// Multi-line answers (printed as `Part 1: ▼`) span the lines up to the next part.
+    pub fn parse_answers(output: &[String]) -> [Option<PartOutput>; 2] {
+        let mut outputs = [None, None];
+        let mut multiline: Option<(usize, Vec<String>)> = None;
+
+        let lines = output.iter().map(|line| {
+            // intermediate results are overwritten with a carriage return.
+            let line = line.rsplit('\r').next().unwrap_or_default();
+            style::strip(line)
+        });
+
+        for line in lines {
+            let part = line
+                .strip_prefix("Part ")
+                .and_then(|l| l.split_once(": "))
+                .and_then(|(part, result)| Some((part.parse::<usize>().ok()?, result)));
+
+            match part {
+                Some((part @ 1..=2, result)) => {
+                    if let Some((index, answer)) = multiline.take() {
+                        outputs[index] = Some(multiline_answer(answer));
+                    }
+
+                    if let Some(timing) = result.strip_prefix('▼') {
+                        multiline = Some((part - 1, vec![format!("▼{timing}")]));
+                    } else {
+                        outputs[part - 1] = Some(parse_answer(result));
+                    }
+                }
+                _ => {
+                    if let Some((_, answer)) = multiline.as_mut() {
+                        answer.push(line);
+                    }
+                }
+            }
+        }
+
+        if let Some((index, answer)) = multiline.take() {
+            outputs[index] = Some(multiline_answer(answer));
+        }
+
+        outputs
+    }
+
+    /// Builds the output of a multi-line answer. The first line holds the timing information.
+    fn multiline_answer(lines: Vec<String>) -> PartOutput {
+        let mut lines = lines.into_iter();
+        let timing = lines.next().unwrap_or_default();
+        let answer = lines.collect::<Vec<String>>().join("\n");
+
+        match parse_answer(&timing) {
+            PartOutput::Answer {
+                duration, nanos, ..
+            } => PartOutput::Answer {
+                answer: answer.trim_end().to_string(),
+                duration,
+                nanos,
+            },
+            PartOutput::Unsolved => PartOutput::Unsolved,
+        }
     }
 
     fn parse_time(line: &str) -> Option<(&str, f64)> {
-        // for possible time formats, see: https://github.com/rust-lang/rust/blob/1.64.0/library/core/src/time.rs#L1176-L1200
         let str_timing = line
             .split(" samples)")
             .next()?
             .split('(')
-            .last()?
+            .next_back()?
             .split('@')
             .next()?
             .trim();
 
-        let parsed_timing = match str_timing {
-            s if s.contains("ns") => s.split("ns").next()?.parse::<f64>().ok(),
-            s if s.contains("µs") => parse_to_float(s, "µs").map(|x| x * 1000_f64),
-            s if s.contains("ms") => parse_to_float(s, "ms").map(|x| x * 1_000_000_f64),
-            s => parse_to_float(s, "s").map(|x| x * 1_000_000_000_f64),
-        }?;
+        let parsed_timing = parse_duration(str_timing)?;
 
         Some((str_timing, parsed_timing))
     }
@@ -202,7 +409,7 @@ mod child_commands {
 
     #[cfg(feature = "test_lib")]
     mod tests {
-        use super::parse_exec_time;
+        use super::{parse_answers, parse_exec_time, PartOutput};
 
         use crate::day;
 
@@ -219,6 +426,8 @@ mod child_commands {
             assert_approx_eq!(res.total_nanos, 74130074.13_f64);
             assert_eq!(res.part_1.unwrap(), "74.13ns");
             assert_eq!(res.part_2.unwrap(), "74.13ms");
+            assert_approx_eq!(res.part_1_nanos.unwrap(), 74.13_f64);
+            assert_approx_eq!(res.part_2_nanos.unwrap(), 74130000_f64);
         }
 
         #[test]
@@ -250,5 +459,54 @@ mod child_commands {
             assert_eq!(res.part_1.is_none(), true);
             assert_eq!(res.part_2.is_none(), true);
         }
+
+        #[test]
+        fn test_parse_answers() {
+            let res = parse_answers(&[
+                "Part 1: \x1b[1m42\x1b[0m\rPart 1: \x1b[1m42\x1b[0m (74.13ns @ 100 samples)".into(),
+                "Part 2: ✖        ".into(),
+            ]);
+            assert_eq!(
+                res[0],
+                Some(PartOutput::Answer {
+                    answer: "42".into(),
+                    duration: Some("74.13ns".into()),
+                    nanos: Some(74.13),
+                })
+            );
+            assert_eq!(res[1], Some(PartOutput::Unsolved));
+        }
+
+        #[test]
+        fn test_parse_multiline_answers() {
+            let res = parse_answers(&[
+                "Part 1: ▼ (1.0ms)".into(),
+                "#..".into(),
+                ".#.".into(),
+                "Part 2: 10 (2.0s)".into(),
+            ]);
+            assert_eq!(
+                res[0],
+                Some(PartOutput::Answer {
+                    answer: "#..\n.#.".into(),
+                    duration: Some("1.0ms".into()),
+                    nanos: Some(1_000_000.0),
+                })
+            );
+            assert_eq!(
+                res[1],
+                Some(PartOutput::Answer {
+                    answer: "10".into(),
+                    duration: Some("2.0s".into()),
+                    nanos: Some(2_000_000_000.0),
+                })
+            );
+        }
+
+        #[test]
+        fn test_parse_missing_answers() {
+            let res = parse_answers(&["thread 'main' panicked".into()]);
+            assert_eq!(res, [None, None]);
+        }
     }
 }
@@ -0,0 +1,238 @@
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::time::Duration;
+
+use crate::generate::Size;
+use crate::style::{self, bold, italic, Colour};
+use crate::template::commands::all::{child_commands, get_path_for_bin};
+use crate::template::commands::gen;
+use crate::template::report::PartOutput;
+use crate::template::{input, Error, EXIT_USAGE};
+use crate::viz::Table;
+use crate::Day;
+
+/// How many inputs of each size are generated for days with an input generator.
+const GENERATED_INPUTS: u64 = 4;
+
+/// An input that is fed to both solvers. Examples for a single part only check that part.
+struct Case {
+    name: String,
+    text: String,
+    parts: Vec<u8>,
+}
+
+/// Feeds the real input, the examples and generated inputs of a day to its solution and to the
+/// `reference` command over stdin, and compares the answers of both per part.
+/// Parts the reference does not answer are not compared.
+pub fn handle(
+    day: Day,
+    reference: &str,
+    is_release: bool,
+    timeout: Option<Duration>,
+) -> Result<(), Error> {
+    if !Path::new(&get_path_for_bin(day)).exists() {
+        return Err(Error::NotScaffolded(day));
+    }
+
+    let mut failures: Vec<Error> = vec![];
+    let cases = collect_cases(day, &mut failures);
+    println!(
+        "{}: {}",
+        bold(format!("Day {day}")),
+        italic(format!(
+            "comparing {} inputs with `{reference}`",
+            cases.len()
+        ))
+    );
+
+    let mut table = Table::new(["Input", "Part", "Answer", "Reference"]);
+    let mut mismatches = 0;
+
+    for case in &cases {
+        let mut run = |cmd: Command| {
+            let mut output = vec![];
+            let result = child_commands::run_command(
+                cmd,
+                day,
+                timeout,
+                Some(&case.text),
+                false,
+                &mut output,
+            );
+            if let Err(e) = result {
+                eprintln!("Error: {e} ({})", case.name);
+                failures.push(e);
+            }
+            output
+        };
+        let ours =
+            child_commands::parse_answers(&run(solution_command(day, is_release))).map(answer);
+        let theirs = parse_reference(&run(reference_command(reference)));
+
+        for &part in &case.parts {
+            let index = usize::from(part - 1);
+            let differs = theirs[index].is_some() && ours[index] != theirs[index];
+            if differs {
+                mismatches += 1;
+            }
+
+            let show = |answer: &Option<String>| answer.clone().unwrap_or_else(|| "✖".into());
+            table.add_row(
+                [
+                    case.name.clone(),
+                    part.to_string(),
+                    show(&ours[index]),
+                    show(&theirs[index]),
+                ],
+                differs.then_some(Colour::Red),
+            );
+        }
+    }
+
+    println!("{table}");
+
+    let mismatch = Error::Mismatch {
+        day,
+        count: mismatches,
+    };
+    match (failures.is_empty(), mismatches) {
+        (true, 0) => {
+            println!("All answers match.");
+            Ok(())
+        }
+        (true, _) => Err(mismatch),
+        // the failures have been reported as they occurred.
+        (false, 0) => Err(Error::Failures(failures)),
+        (false, _) => {
+            eprintln!("Error: {mismatch}");
+            failures.push(mismatch);
+            Err(Error::Failures(failures))
+        }
+    }
+}
+
+/// Collects the real input, the examples (shared and per part) and generated inputs of a day.
+/// Days without an input generator are compared on their files only, other generator failures
+/// are reported and added to `failures`.
+fn collect_cases(day: Day, failures: &mut Vec<Error>) -> Vec<Case> {
+    let mut cases = vec![];
+
+    for folder in ["inputs", "examples"] {
+        if let Ok(input) = input::load(folder, day, false) {
+            cases.push(Case {
+                name: format!("{folder}/{day}.txt"),
+                text: input.text,
+                parts: vec![1, 2],
+            });
+        }
+    }
+
+    for part in [1, 2] {
+        if let Ok(input) = input::load_part("examples", day, part, false) {
+            cases.push(Case {
+                name: format!("examples/{day}-{part}.txt"),
+                text: input.text,
+                parts: vec![part],
+            });
+        }
+    }
+
+    'generate: for size in [Size::Small, Size::Medium] {
+        for seed in 0..GENERATED_INPUTS {
+            let name = format!("--seed {seed} --size {size}");
+            match gen::generate(day, seed, size, Stdio::null()) {
+                Ok(text) => cases.push(Case {
+                    name,
+                    text,
+                    parts: vec![1, 2],
+                }),
+                Err(Error::ChildFailure {
+                    code: Some(EXIT_USAGE),
+                    ..
+                }) => {
+                    println!(
+                        "{}",
+                        italic("No input generator, skipping generated inputs.")
+                    );
+                    break 'generate;
+                }
+                Err(e) => {
+                    eprintln!("Error: could not generate an input: {e} ({name})");
+                    failures.push(e);
+                    break 'generate;
+                }
+            }
+        }
+    }
+
+    cases
+}
+
+fn solution_command(day: Day, is_release: bool) -> Command {
+    let mut cmd = Command::new("cargo");
+    cmd.args(["run", "--quiet", "--bin", &day.to_string()]);
+    if is_release {
+        cmd.arg("--release");
+    }
+    if cfg!(feature = "embed_inputs") {
+        // build the solution like `solve` and `all` do, it still reads the cases from stdin.
+        cmd.args(["--features", "embed_inputs"]);
+    }
+    cmd.args(["--", "--color=never", "--stdin"]);
+    cmd
+}
+
+fn reference_command(reference: &str) -> Command {
+    let (shell, flag) = if cfg!(windows) {
+        ("cmd", "/C")
+    } else {
+        ("sh", "-c")
+    };
+    let mut cmd = Command::new(shell);
+    cmd.args([flag, reference]);
+    cmd
+}
+
+fn answer(output: Option<PartOutput>) -> Option<String> {
+    match output {
+        Some(PartOutput::Answer { answer, .. }) => Some(answer),
+        _ => None,
+    }
+}
+
+/// Reads the answers of a reference solver. Solvers built from this template print `Part 1: …`
+/// lines, other solvers are expected to print the answer to each part on its own line.
+fn parse_reference(output: &[String]) -> [Option<String>; 2] {
+    let answers = child_commands::parse_answers(output);
+    if answers.iter().any(Option::is_some) {
+        return answers.map(answer);
+    }
+
+    let mut lines = output
+        .iter()
+        .map(|line| style::strip(line).trim().to_string())
+        .filter(|line| !line.is_empty());
+    [lines.next(), lines.next()]
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::parse_reference;
+
+    fn lines(output: &str) -> Vec<String> {
+        output.lines().map(String::from).collect()
+    }
+
+    #[test]
+    fn parses_reference_answers() {
+        assert_eq!(
+            parse_reference(&lines("Part 1: 6440 (1.2ms)\nPart 2: ✖\n")),
+            [Some("6440".into()), None]
+        );
+        assert_eq!(
+            parse_reference(&lines("\n6440\n  5905  \ndebug output\n")),
+            [Some("6440".into()), Some("5905".into())]
+        );
+        assert_eq!(parse_reference(&lines("42\n")), [Some("42".into()), None]);
+    }
+}
@@ -0,0 +1,60 @@
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+use crate::generate::Size;
+use crate::template::commands::{all::get_path_for_bin, wait_for_child};
+use crate::template::Error;
+use crate::Day;
+
+/// Runs the input generator of a solution and writes its output to `data/generated/DD-size-seed.txt`.
+pub fn handle(day: Day, seed: u64, size: Size) -> Result<(), Error> {
+    let input = generate(day, seed, size, Stdio::inherit())?;
+
+    let path = format!("data/generated/{day}-{size}-{seed}.txt");
+    fs::create_dir_all("data/generated").map_err(Error::io_at("data/generated"))?;
+    fs::write(&path, input).map_err(Error::io_at(&path))?;
+
+    println!("Wrote generated input to \"{path}\".");
+    Ok(())
+}
+
+/// Runs the input generator of a solution and returns the input it generated.
+/// Fails with [`Error::ChildFailure`] and [`EXIT_USAGE`](crate::template::EXIT_USAGE) if the day has
+/// no generator, whose error message goes to `stderr`.
+pub(crate) fn generate(day: Day, seed: u64, size: Size, stderr: Stdio) -> Result<String, Error> {
+    if !Path::new(&get_path_for_bin(day)).exists() {
+        return Err(Error::NotScaffolded(day));
+    }
+
+    let seed_str = seed.to_string();
+    let size_str = size.to_string();
+    let day_str = day.to_string();
+    let cmd_args = [
+        "run",
+        "--quiet",
+        "--bin",
+        &day_str,
+        "--",
+        "--generate",
+        "--seed",
+        &seed_str,
+        "--size",
+        &size_str,
+    ];
+
+    let mut cmd = Command::new("cargo")
+        .args(cmd_args)
+        .stdout(Stdio::piped())
+        .stderr(stderr)
+        .spawn()?;
+
+    let mut input = String::new();
+    if let Some(mut stdout) = cmd.stdout.take() {
+        stdout.read_to_string(&mut input)?;
+    }
+    wait_for_child(&mut cmd, day, None)?;
+
+    Ok(input)
+}
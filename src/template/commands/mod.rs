@@ -1,5 +1,59 @@
+use std::{
+    process::{Child, ExitStatus},
+    thread,
+    time::{Duration, Instant},
+};
+
+use crate::template::Error;
+use crate::Day;
+
 pub mod all;
+pub mod diff_test;
 pub mod download;
+pub mod gen;
 pub mod read;
 pub mod scaffold;
+pub mod shrink;
 pub mod solve;
+
+/// How often a running child process is polled while waiting with a timeout.
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Waits for the solution process of `day` to exit, killing it once `timeout` has elapsed.
+/// Returns an error if the child exits with a non-zero status or times out.
+pub(crate) fn wait_for_child(
+    child: &mut Child,
+    day: Day,
+    timeout: Option<Duration>,
+) -> Result<(), Error> {
+    let status = match timeout {
+        None => child.wait()?,
+        Some(limit) => {
+            let started = Instant::now();
+            loop {
+                if let Some(status) = child.try_wait()? {
+                    break status;
+                }
+                if started.elapsed() >= limit {
+                    child.kill()?;
+                    child.wait()?;
+                    return Err(Error::Timeout { day, limit });
+                }
+                thread::sleep(POLL_INTERVAL);
+            }
+        }
+    };
+
+    check_status(status, day)
+}
+
+fn check_status(status: ExitStatus, day: Day) -> Result<(), Error> {
+    if status.success() {
+        Ok(())
+    } else {
+        Err(Error::ChildFailure {
+            day,
+            code: status.code(),
+        })
+    }
+}
@@ -1,9 +1,9 @@
 use std::{
     fs::{File, OpenOptions},
     io::Write,
-    process,
 };
 
+use crate::template::Error;
 use crate::Day;
 
 const MODULE_TEMPLATE: &str = r#"advent_of_code::solution!(DAY_NUMBER);
@@ -39,56 +39,36 @@ fn safe_create_file(path: &str) -> Result<File, std::io::Error> {
 }
 
 fn create_file(path: &str) -> Result<File, std::io::Error> {
-    OpenOptions::new().write(true).create(true).open(path)
+    // Never truncate, so that scaffolding a day keeps an input that was downloaded before.
+    OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .open(path)
 }
 
-pub fn handle(day: Day) {
+pub fn handle(day: Day) -> Result<(), Error> {
     let input_path = format!("data/inputs/{day}.txt");
     let example_path = format!("data/examples/{day}.txt");
     let module_path = format!("src/bin/{day}.rs");
 
-    let mut file = match safe_create_file(&module_path) {
-        Ok(file) => file,
-        Err(e) => {
-            eprintln!("Failed to create module file: {e}");
-            process::exit(1);
-        }
-    };
+    let mut file = safe_create_file(&module_path).map_err(Error::io_at(&module_path))?;
 
-    match file.write_all(
+    file.write_all(
         MODULE_TEMPLATE
             .replace("DAY_NUMBER", &day.into_inner().to_string())
             .as_bytes(),
-    ) {
-        Ok(()) => {
-            println!("Created module file \"{}\"", &module_path);
-        }
-        Err(e) => {
-            eprintln!("Failed to write module contents: {e}");
-            process::exit(1);
-        }
-    }
+    )
+    .map_err(Error::io_at(&module_path))?;
+    println!("Created module file \"{}\"", &module_path);
 
-    match create_file(&input_path) {
-        Ok(_) => {
-            println!("Created empty input file \"{}\"", &input_path);
-        }
-        Err(e) => {
-            eprintln!("Failed to create input file: {e}");
-            process::exit(1);
-        }
-    }
+    create_file(&input_path).map_err(Error::io_at(&input_path))?;
+    println!("Created empty input file \"{}\"", &input_path);
 
-    match create_file(&example_path) {
-        Ok(_) => {
-            println!("Created empty example file \"{}\"", &example_path);
-        }
-        Err(e) => {
-            eprintln!("Failed to create example file: {e}");
-            process::exit(1);
-        }
-    }
+    create_file(&example_path).map_err(Error::io_at(&example_path))?;
+    println!("Created empty example file \"{}\"", &example_path);
 
     println!("---");
     println!("🎄 Type `cargo solve {}` to run your solution.", day);
+    Ok(())
 }
@@ -0,0 +1,40 @@
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+use crate::template::commands::{all::get_path_for_bin, wait_for_child};
+use crate::template::Error;
+use crate::{style, Day};
+
+/// Shrinks the input of a day while `part` keeps disagreeing with `against` (a variant of the part,
+/// or `ledger`) or keeps panicking. The solution writes the result to `data/examples/DD-shrunk.txt`.
+pub fn handle(day: Day, part: u8, against: &str) -> Result<(), Error> {
+    if !Path::new(&get_path_for_bin(day)).exists() {
+        return Err(Error::NotScaffolded(day));
+    }
+
+    let part_str = part.to_string();
+    let day_str = day.to_string();
+    let color = style::child_arg();
+    let cmd_args = [
+        "run",
+        "--quiet",
+        "--release",
+        "--bin",
+        &day_str,
+        "--",
+        &color,
+        "--shrink",
+        "--part",
+        &part_str,
+        "--against",
+        against,
+    ];
+
+    let mut cmd = Command::new("cargo")
+        .args(cmd_args)
+        .stdout(Stdio::inherit())
+        .stderr(Stdio::inherit())
+        .spawn()?;
+
+    wait_for_child(&mut cmd, day, None)
+}
@@ -1,15 +1,57 @@
+use std::path::Path;
 use std::process::{Command, Stdio};
+use std::time::Duration;
 
+use crate::style;
+use crate::template::commands::{all::get_path_for_bin, wait_for_child};
+use crate::template::Error;
+use crate::viz::Visualize;
 use crate::Day;
 
-pub fn handle(day: Day, release: bool, time: bool, submit_part: Option<u8>) {
+/// The flags of `cargo solve`, most of which are passed on to the solution.
+#[derive(Debug, Clone, Default)]
+pub struct Options {
+    pub release: bool,
+    pub time: bool,
+    pub submit_part: Option<u8>,
+    pub normalize: bool,
+    pub timeout: Option<Duration>,
+    pub visualize: Option<Visualize>,
+    /// Check and benchmark the variants of each part instead of running the parts.
+    pub variants: bool,
+    /// Arguments after `--`, passed on to the solution as they are.
+    pub args: Vec<String>,
+}
+
+pub fn handle(day: Day, options: Options) -> Result<(), Error> {
+    let Options {
+        release,
+        time,
+        submit_part,
+        normalize,
+        timeout,
+        visualize,
+        variants,
+        args,
+    } = options;
+
+    if !Path::new(&get_path_for_bin(day)).exists() {
+        return Err(Error::NotScaffolded(day));
+    }
+
     let mut cmd_args = vec!["run".to_string(), "--bin".to_string(), day.to_string()];
 
     if release {
         cmd_args.push("--release".to_string());
     }
 
+    if cfg!(feature = "embed_inputs") {
+        cmd_args.push("--features".to_string());
+        cmd_args.push("embed_inputs".to_string());
+    }
+
     cmd_args.push("--".to_string());
+    cmd_args.push(style::child_arg());
 
     if let Some(submit_part) = submit_part {
         cmd_args.push("--submit".to_string());
@@ -20,12 +62,25 @@ pub fn handle(day: Day, release: bool, time: bool, submit_part: Option<u8>) {
         cmd_args.push("--time".to_string());
     }
 
+    if normalize {
+        cmd_args.push("--normalize".to_string());
+    }
+
+    if variants {
+        cmd_args.push("--variants".to_string());
+    }
+
+    if let Some(visualize) = visualize {
+        cmd_args.extend(visualize.to_args());
+    }
+
+    cmd_args.extend(args);
+
     let mut cmd = Command::new("cargo")
         .args(&cmd_args)
         .stdout(Stdio::inherit())
         .stderr(Stdio::inherit())
-        .spawn()
-        .unwrap();
+        .spawn()?;
 
-    cmd.wait().unwrap();
+    wait_for_child(&mut cmd, day, timeout)
 }
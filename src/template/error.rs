@@ -0,0 +1,274 @@
+/// Error type shared by all commands of the command-line.
+use std::{fmt::Display, io, time::Duration};
+
+use crate::template::{aoc_cli::AocCommandError, readme_benchmarks};
+use crate::Day;
+
+/// Exit code for invalid command-line arguments.
+pub const EXIT_USAGE: i32 = 2;
+
+/// An error returned by a command.
+///
+/// # Exit codes
+/// Each variant maps to a distinct exit code so that scripts can tell failures apart:
+///
+/// | Code | Meaning                                                  |
+/// | ---- | -------------------------------------------------------- |
+/// | `0`  | success                                                  |
+/// | `1`  | I/O error, e.g. a file could not be written              |
+/// | `2`  | invalid command-line arguments ([`EXIT_USAGE`])          |
+/// | `3`  | `aoc-cli` is missing or failed                           |
+/// | `4`  | output could not be parsed, e.g. the readme table        |
+/// | `5`  | the day has not been scaffolded yet                      |
+/// | `6`  | the solution crashed or exited with a non-zero status    |
+/// | `7`  | the solution did not finish within the timeout           |
+/// | `8`  | an answer differs from the answer ledger                 |
+/// | `9`  | an answer differs from the reference solver              |
+///
+/// [`Error::Failures`] exits with the code of its most severe failure, the first of them on a tie:
+/// failures to run anything come before unparseable output, which comes before wrong answers.
+#[derive(Debug)]
+pub enum Error {
+    Io {
+        path: Option<String>,
+        source: io::Error,
+    },
+    AocCli(AocCommandError),
+    Parse(String),
+    NotScaffolded(Day),
+    ChildFailure {
+        day: Day,
+        code: Option<i32>,
+    },
+    Timeout {
+        day: Day,
+        limit: Duration,
+    },
+    WrongAnswer {
+        day: Day,
+        parts: Vec<u8>,
+    },
+    Mismatch {
+        day: Day,
+        count: usize,
+    },
+    /// Several failures of a command that keeps going, e.g. `cargo all`.
+    /// Each of them has been reported when it occurred. Never empty.
+    Failures(Vec<Error>),
+}
+
+impl Error {
+    /// Returns a function that wraps an [`io::Error`] which occurred while accessing `path`.
+    pub fn io_at(path: &str) -> impl FnOnce(io::Error) -> Self + '_ {
+        move |source| Self::Io {
+            path: Some(path.to_string()),
+            source,
+        }
+    }
+
+    /// The exit code the command-line exits with when this error occurs.
+    #[must_use]
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Error::Io { .. } => 1,
+            Error::AocCli(_) => 3,
+            Error::Parse(_) => 4,
+            Error::NotScaffolded(_) => 5,
+            Error::ChildFailure { .. } => 6,
+            Error::Timeout { .. } => 7,
+            Error::WrongAnswer { .. } => 8,
+            Error::Mismatch { .. } => 9,
+            // `max_by_key` returns the last of equally severe failures.
+            Error::Failures(failures) => failures
+                .iter()
+                .rev()
+                .max_by_key(|e| e.severity())
+                .map_or(1, Error::exit_code),
+        }
+    }
+
+    /// How badly a command failed, to pick the exit code of [`Error::Failures`].
+    fn severity(&self) -> u8 {
+        match self {
+            Error::Io { .. }
+            | Error::AocCli(_)
+            | Error::NotScaffolded(_)
+            | Error::ChildFailure { .. }
+            | Error::Timeout { .. } => 2,
+            Error::Parse(_) => 1,
+            Error::WrongAnswer { .. } | Error::Mismatch { .. } => 0,
+            Error::Failures(failures) => failures.iter().map(Error::severity).max().unwrap_or(0),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Io {
+                path: Some(path),
+                source,
+            } => write!(f, "could not access \"{path}\": {source}"),
+            Error::Io { path: None, source } => write!(f, "I/O error: {source}"),
+            Error::AocCli(AocCommandError::CommandNotFound) => write!(
+                f,
+                "command \"aoc\" not found or not callable. Try running \"cargo install aoc-cli\" to install it."
+            ),
+            Error::AocCli(e) => write!(f, "failed to call aoc-cli: {e}"),
+            Error::Parse(message) => write!(f, "could not parse {message}"),
+            Error::NotScaffolded(day) => write!(
+                f,
+                "day {day} has not been scaffolded yet. Run \"cargo scaffold {day}\" to create it."
+            ),
+            Error::ChildFailure {
+                day,
+                code: Some(code),
+            } => write!(f, "solution for day {day} exited with status {code}."),
+            Error::ChildFailure { day, code: None } => {
+                write!(f, "solution for day {day} was terminated by a signal.")
+            }
+            Error::Timeout { day, limit } => {
+                write!(f, "solution for day {day} did not finish within {limit:?}.")
+            }
+            Error::WrongAnswer { day, parts } => {
+                let parts: Vec<String> = parts.iter().map(ToString::to_string).collect();
+                write!(
+                    f,
+                    "day {day} part {} differs from the answer ledger.",
+                    parts.join(" & ")
+                )
+            }
+            Error::Mismatch { day, count } => write!(
+                f,
+                "{count} answer(s) for day {day} differ from the reference solver."
+            ),
+            Error::Failures(failures) => write!(
+                f,
+                "{} failure(s), see the errors above.",
+                failures.len()
+            ),
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(source: io::Error) -> Self {
+        Error::Io { path: None, source }
+    }
+}
+
+impl From<AocCommandError> for Error {
+    fn from(e: AocCommandError) -> Self {
+        Error::AocCli(e)
+    }
+}
+
+impl From<readme_benchmarks::Error> for Error {
+    fn from(e: readme_benchmarks::Error) -> Self {
+        match e {
+            readme_benchmarks::Error::Parser(message) => Error::Parse(format!("README: {message}")),
+            readme_benchmarks::Error::IO(source) => Error::Io {
+                path: Some("README.md".into()),
+                source,
+            },
+        }
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use std::{io, time::Duration};
+
+    use super::Error;
+    use crate::day;
+
+    #[test]
+    fn distinct_exit_codes() {
+        let errors = [
+            Error::from(io::Error::other("disk full")),
+            Error::AocCli(crate::template::aoc_cli::AocCommandError::CommandNotFound),
+            Error::Parse("output".into()),
+            Error::NotScaffolded(day!(1)),
+            Error::ChildFailure {
+                day: day!(1),
+                code: Some(101),
+            },
+            Error::Timeout {
+                day: day!(1),
+                limit: Duration::from_secs(1),
+            },
+            Error::WrongAnswer {
+                day: day!(1),
+                parts: vec![2],
+            },
+            Error::Mismatch {
+                day: day!(1),
+                count: 1,
+            },
+        ];
+
+        let mut codes: Vec<i32> = errors.iter().map(Error::exit_code).collect();
+        codes.push(super::EXIT_USAGE);
+        codes.sort_unstable();
+        let count = codes.len();
+        codes.dedup();
+        assert_eq!(codes.len(), count);
+        assert!(codes.iter().all(|&code| code > 0));
+
+        // several failures exit like the most severe of them, the first one on a tie.
+        let wrong_answer = || Error::WrongAnswer {
+            day: day!(1),
+            parts: vec![1],
+        };
+        let crash = || Error::ChildFailure {
+            day: day!(2),
+            code: None,
+        };
+        let failures = |errors| Error::Failures(errors).exit_code();
+        assert_eq!(failures(vec![wrong_answer(), crash()]), 6);
+        assert_eq!(
+            failures(vec![wrong_answer(), Error::Parse("output".into())]),
+            4
+        );
+        assert_eq!(failures(vec![Error::NotScaffolded(day!(3)), crash()]), 5);
+        assert_eq!(
+            failures(vec![
+                Error::Mismatch {
+                    day: day!(1),
+                    count: 1
+                },
+                wrong_answer()
+            ]),
+            9
+        );
+    }
+
+    #[test]
+    fn formats_messages() {
+        assert_eq!(
+            Error::NotScaffolded(day!(4)).to_string(),
+            "day 04 has not been scaffolded yet. Run \"cargo scaffold 04\" to create it."
+        );
+        assert_eq!(
+            Error::WrongAnswer {
+                day: day!(4),
+                parts: vec![1, 2]
+            }
+            .to_string(),
+            "day 04 part 1 & 2 differs from the answer ledger."
+        );
+        assert_eq!(
+            Error::io_at("data/inputs/04.txt")(io::Error::other("denied")).to_string(),
+            "could not access \"data/inputs/04.txt\": denied"
+        );
+    }
+}
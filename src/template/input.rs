@@ -0,0 +1,279 @@
+/// Module that loads puzzle inputs and examples from the `data` directory.
+/// Loading detects common problems with input files and reports them as errors or warnings.
+use std::{
+    env,
+    fmt::Display,
+    fs,
+    io::{self, Read},
+    path::{Path, PathBuf},
+};
+
+use crate::Day;
+
+/// An error which can be returned when loading an input file.
+#[derive(Debug)]
+pub enum InputError {
+    /// The file does not exist.
+    Missing {
+        path: PathBuf,
+        folder: String,
+        day: Day,
+    },
+    /// The file exists, but could not be read.
+    Io { path: PathBuf, source: io::Error },
+}
+
+impl std::error::Error for InputError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            InputError::Io { source, .. } => Some(source),
+            InputError::Missing { .. } => None,
+        }
+    }
+}
+
+impl Display for InputError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InputError::Missing { path, folder, day } => {
+                write!(
+                    f,
+                    "could not find {} file \"{}\". ",
+                    kind(folder),
+                    path.display()
+                )?;
+                f.write_str(&suggestion(folder, *day))
+            }
+            InputError::Io { path, source } => {
+                write!(f, "could not read \"{}\": {source}", path.display())
+            }
+        }
+    }
+}
+
+/// A problem with an input file that does not prevent it from being loaded.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InputWarning {
+    /// The file is empty, e.g. because it was created by `cargo scaffold` and never filled.
+    Empty {
+        path: PathBuf,
+        folder: String,
+        day: Day,
+    },
+    /// The file uses `\r\n` line endings.
+    CrlfLineEndings { path: PathBuf },
+    /// The last line of the file is not terminated by a newline.
+    MissingTrailingNewline { path: PathBuf },
+}
+
+impl Display for InputWarning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InputWarning::Empty { path, folder, day } => {
+                write!(f, "{} file \"{}\" is empty. ", kind(folder), path.display())?;
+                f.write_str(&suggestion(folder, *day))
+            }
+            InputWarning::CrlfLineEndings { path } => write!(
+                f,
+                "\"{}\" uses CRLF line endings. Pass `--normalize` to convert them to LF.",
+                path.display()
+            ),
+            InputWarning::MissingTrailingNewline { path } => write!(
+                f,
+                "\"{}\" does not end with a newline. Pass `--normalize` to append one.",
+                path.display()
+            ),
+        }
+    }
+}
+
+/// The contents of an input file, together with any problems detected while loading it.
+#[derive(Debug, Clone)]
+pub struct Input {
+    pub text: String,
+    pub warnings: Vec<InputWarning>,
+}
+
+fn kind(folder: &str) -> &str {
+    match folder {
+        "inputs" => "input",
+        "examples" => "example",
+        _ => folder,
+    }
+}
+
+fn suggestion(folder: &str, day: Day) -> String {
+    match folder {
+        "inputs" => format!("Run \"cargo download {day}\" to fetch your puzzle input."),
+        "examples" => format!(
+            "Paste the example from the puzzle description into \"data/examples/{day}.txt\"."
+        ),
+        _ => format!("Create the file \"data/{folder}/{day}.txt\"."),
+    }
+}
+
+#[must_use]
+pub fn get_path(folder: &str, day: Day) -> PathBuf {
+    let cwd = env::current_dir().unwrap_or_default();
+    cwd.join("data").join(folder).join(format!("{day}.txt"))
+}
+
+/// Returns the path of a file that only applies to one part, e.g. an example that differs between parts.
+#[must_use]
+pub fn get_part_path(folder: &str, day: Day, part: u8) -> PathBuf {
+    let cwd = env::current_dir().unwrap_or_default();
+    cwd.join("data")
+        .join(folder)
+        .join(format!("{day}-{part}.txt"))
+}
+
+/// Converts CRLF line endings to LF and makes sure that non-empty text ends with a newline.
+#[must_use]
+pub fn normalize(text: &str) -> String {
+    let mut normalized = text.replace("\r\n", "\n");
+    if !normalized.is_empty() && !normalized.ends_with('\n') {
+        normalized.push('\n');
+    }
+    normalized
+}
+
+/// Detects problems with the contents of an input file.
+#[must_use]
+pub fn check(text: &str, path: &Path, folder: &str, day: Day) -> Vec<InputWarning> {
+    if text.trim().is_empty() {
+        return vec![InputWarning::Empty {
+            path: path.to_path_buf(),
+            folder: folder.to_string(),
+            day,
+        }];
+    }
+
+    let mut warnings = vec![];
+
+    if text.contains("\r\n") {
+        warnings.push(InputWarning::CrlfLineEndings {
+            path: path.to_path_buf(),
+        });
+    }
+
+    if !text.ends_with('\n') {
+        warnings.push(InputWarning::MissingTrailingNewline {
+            path: path.to_path_buf(),
+        });
+    }
+
+    warnings
+}
+
+/// Loads `data/<folder>/<day>.txt`. If `normalize` is set, line endings and the trailing newline are fixed
+/// (see [`normalize`]) and no warnings are reported for them.
+pub fn load(folder: &str, day: Day, normalize: bool) -> Result<Input, InputError> {
+    load_path(get_path(folder, day), folder, day, normalize)
+}
+
+/// Loads `data/<folder>/<day>-<part>.txt`, see [`load`].
+pub fn load_part(folder: &str, day: Day, part: u8, normalize: bool) -> Result<Input, InputError> {
+    load_path(get_part_path(folder, day, part), folder, day, normalize)
+}
+
+/// Reads an input from stdin, e.g. when it is fed to a solution by `cargo diff-test`.
+/// Warnings name `<stdin>` instead of a file.
+pub fn load_stdin(day: Day, normalize: bool) -> Result<Input, InputError> {
+    let path = PathBuf::from("<stdin>");
+    let mut text = String::new();
+    io::stdin()
+        .read_to_string(&mut text)
+        .map_err(|source| InputError::Io {
+            path: path.clone(),
+            source,
+        })?;
+
+    Ok(Input::at(text, &path, "inputs", day, normalize))
+}
+
+fn load_path(path: PathBuf, folder: &str, day: Day, normalize: bool) -> Result<Input, InputError> {
+    let text = fs::read_to_string(&path).map_err(|source| match source.kind() {
+        io::ErrorKind::NotFound => InputError::Missing {
+            path: path.clone(),
+            folder: folder.to_string(),
+            day,
+        },
+        _ => InputError::Io {
+            path: path.clone(),
+            source,
+        },
+    })?;
+
+    Ok(Input::at(text, &path, folder, day, normalize))
+}
+
+impl Input {
+    /// Wraps the contents of `data/<folder>/<day>.txt`, e.g. when it was embedded at compile time.
+    #[must_use]
+    pub fn new(text: String, folder: &str, day: Day, normalize: bool) -> Self {
+        Self::at(text, &get_path(folder, day), folder, day, normalize)
+    }
+
+    fn at(text: String, path: &Path, folder: &str, day: Day, normalize: bool) -> Self {
+        let text = if normalize {
+            self::normalize(&text)
+        } else {
+            text
+        };
+
+        Self {
+            warnings: check(&text, path, folder, day),
+            text,
+        }
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::{check, normalize, InputError, InputWarning};
+    use crate::day;
+
+    #[test]
+    fn normalizes_line_endings() {
+        assert_eq!(normalize("a\r\nb\r\n"), "a\nb\n");
+        assert_eq!(normalize("a\nb"), "a\nb\n");
+        assert_eq!(normalize(""), "");
+    }
+
+    #[test]
+    fn warns_about_malformed_inputs() {
+        let path = PathBuf::from("data/inputs/01.txt");
+
+        assert_eq!(check("1\n2\n", &path, "inputs", day!(1)), vec![]);
+        assert_eq!(
+            check("1\r\n2", &path, "inputs", day!(1)),
+            vec![
+                InputWarning::CrlfLineEndings { path: path.clone() },
+                InputWarning::MissingTrailingNewline { path: path.clone() }
+            ]
+        );
+        assert_eq!(
+            check(" \n", &path, "inputs", day!(1)),
+            vec![InputWarning::Empty {
+                path: path.clone(),
+                folder: "inputs".into(),
+                day: day!(1)
+            }]
+        );
+    }
+
+    #[test]
+    fn suggests_download_for_missing_input() {
+        let e = InputError::Missing {
+            path: PathBuf::from("data/inputs/07.txt"),
+            folder: "inputs".into(),
+            day: day!(7),
+        };
+        assert_eq!(
+            e.to_string(),
+            "could not find input file \"data/inputs/07.txt\". Run \"cargo download 07\" to fetch your puzzle input."
+        );
+    }
+}
@@ -0,0 +1,71 @@
+/// Module that reads the answer ledger, i.e. the known-good answers for each day.
+/// Answers live in `data/answers/DD.txt`: the first line holds the answer to part one, the second line the answer to part two.
+/// Empty lines (or a missing file) mark answers that are not known yet.
+use std::fs;
+
+use crate::Day;
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Answers {
+    pub part_1: Option<String>,
+    pub part_2: Option<String>,
+}
+
+impl Answers {
+    /// Parses the contents of a ledger file.
+    #[must_use]
+    pub fn parse(s: &str) -> Self {
+        let mut lines = s.lines().map(|line| {
+            let line = line.trim();
+            (!line.is_empty()).then(|| line.to_string())
+        });
+
+        Self {
+            part_1: lines.next().flatten(),
+            part_2: lines.next().flatten(),
+        }
+    }
+
+    /// Returns the known answer for `part`, if any.
+    #[must_use]
+    pub fn get(&self, part: u8) -> Option<&str> {
+        match part {
+            1 => self.part_1.as_deref(),
+            2 => self.part_2.as_deref(),
+            _ => None,
+        }
+    }
+}
+
+#[must_use]
+pub fn get_path(day: Day) -> String {
+    format!("data/answers/{day}.txt")
+}
+
+/// Reads the ledger entry for `day`. Days without a ledger file have no known answers.
+#[must_use]
+pub fn read(day: Day) -> Answers {
+    fs::read_to_string(get_path(day))
+        .map(|s| Answers::parse(&s))
+        .unwrap_or_default()
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::Answers;
+
+    #[test]
+    fn parses_both_parts() {
+        let answers = Answers::parse("142\n281\n");
+        assert_eq!(answers.get(1), Some("142"));
+        assert_eq!(answers.get(2), Some("281"));
+        assert_eq!(answers.get(3), None);
+    }
+
+    #[test]
+    fn parses_unknown_parts() {
+        assert_eq!(Answers::parse(""), Answers::default());
+        assert_eq!(Answers::parse("\n  281  ").get(1), None);
+        assert_eq!(Answers::parse("\n  281  ").get(2), Some("281"));
+    }
+}
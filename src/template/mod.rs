@@ -1,34 +1,129 @@
+use std::env;
+
 use crate::Day;
-use std::{env, fs};
 
 pub mod aoc_cli;
+pub mod benchmark_chart;
 pub mod commands;
+mod error;
+pub mod input;
+pub mod ledger;
 pub mod readme_benchmarks;
+pub mod report;
 pub mod runner;
 
-pub const ANSI_ITALIC: &str = "\x1b[3m";
-pub const ANSI_BOLD: &str = "\x1b[1m";
-pub const ANSI_RESET: &str = "\x1b[0m";
+pub use error::{Error, EXIT_USAGE};
 
 /// Helper function that reads a text file to a string.
+/// Panics with a message that names the file and how to create it if it can not be read.
 #[must_use]
 pub fn read_file(folder: &str, day: Day) -> String {
-    let cwd = env::current_dir().unwrap();
-    let filepath = cwd.join("data").join(folder).join(format!("{day}.txt"));
-    let f = fs::read_to_string(filepath);
-    f.expect("could not open input file")
+    match input::load(folder, day, false) {
+        Ok(input) => input.text,
+        Err(e) => panic!("{e}"),
+    }
+}
+
+/// Helper function that reads a text file that only applies to one part, e.g. `data/examples/08-2.txt`.
+/// Panics like [`read_file`] if it can not be read.
+#[must_use]
+pub fn read_file_part(folder: &str, day: Day, part: u8) -> String {
+    match input::load_part(folder, day, part, false) {
+        Ok(input) => input.text,
+        Err(e) => panic!("{e}"),
+    }
+}
+
+/// Returns the value of an option passed to the solution as `--name <value>` or `--name=<value>`,
+/// e.g. with `cargo solve 2 -- --bag "12 red, 13 green, 14 blue"`.
+#[must_use]
+pub fn arg_value(name: &str) -> Option<String> {
+    let args: Vec<String> = env::args().collect();
+    args.iter()
+        .enumerate()
+        .find_map(|(i, arg)| match arg.strip_prefix(name)? {
+            "" => args.get(i + 1).cloned(),
+            value => value.strip_prefix('=').map(String::from),
+        })
+}
+
+/// Returns whether a flag was passed to the solution, e.g. with `cargo solve 2 -- --explain`.
+#[must_use]
+pub fn has_flag(name: &str) -> bool {
+    env::args().any(|arg| arg == name)
 }
 
 /// Creates the constant `DAY` and sets up the input and runner for each part.
+///
+/// With the `embed_inputs` feature, the input is compiled into the binary from `data/inputs/<bin>.txt`
+/// instead of being read at runtime.
+///
+/// An input generator for `cargo gen` can be registered next to the parts with
+/// `solution!(7, generate = generate)`, see [`generate`](crate::generate).
+///
+/// Alternative implementations of a part are registered as named variants, which
+/// `cargo solve DD --variants` checks against the part and benchmarks:
+/// `solution!(7, variants = [2 => [part_two_bruteforce]])`. `cargo shrink DD --part 2 --against <variant>`
+/// minimises an input for which a part and one of its variants disagree.
 #[macro_export]
 macro_rules! solution {
-    ($day:expr) => {
+    (@generator) => {
+        None
+    };
+    (@generator $generate:ident) => {
+        Some($generate as advent_of_code::generate::Generator)
+    };
+    (@part 1) => {
+        ("part_one", part_one)
+    };
+    (@part 2) => {
+        ("part_two", part_two)
+    };
+
+    (
+        $day:expr
+        $(, generate = $generate:ident)?
+        $(, variants = [$($part:tt => [$($variant:ident),+ $(,)?]),+ $(,)?])?
+        $(,)?
+    ) => {
         /// The current day.
         const DAY: advent_of_code::Day = advent_of_code::day!($day);
 
         fn main() {
             use advent_of_code::template::runner::*;
-            let input = advent_of_code::template::read_file("inputs", DAY);
+            run_generator(DAY, advent_of_code::solution!(@generator $($generate)?));
+            let variants = variants_requested(DAY, &[$($($part),+)?]);
+            #[cfg(feature = "embed_inputs")]
+            let input = read_embedded_input(
+                DAY,
+                include_str!(concat!(
+                    env!("CARGO_MANIFEST_DIR"),
+                    "/data/inputs/",
+                    env!("CARGO_BIN_NAME"),
+                    ".txt"
+                )),
+            );
+            #[cfg(not(feature = "embed_inputs"))]
+            let input = read_input(DAY);
+            if let Some(part) = shrink_requested() {
+                $($(
+                    if part == $part {
+                        let (name, func) = advent_of_code::solution!(@part $part);
+                        run_shrink(DAY, &input, $part, &[(name, func), $((stringify!($variant), $variant)),+]);
+                    }
+                )+)?
+                match part {
+                    1 => run_shrink(DAY, &input, 1, &[("part_one", part_one)]),
+                    _ => run_shrink(DAY, &input, 2, &[("part_two", part_two)]),
+                }
+            }
+            if variants {
+                $($(
+                    let (name, part) = advent_of_code::solution!(@part $part);
+                    run_variants(&input, $part, &[(name, part), $((stringify!($variant), $variant)),+]);
+                )+)?
+                return;
+            }
             run_part(part_one, &input, DAY, 1);
             run_part(part_two, &input, DAY, 2);
         }
@@ -1,10 +1,13 @@
 /// Module that updates the readme me with timing information.
 /// The approach taken is similar to how `aoc-readme-stars` handles this.
-use std::{fs, io};
+use std::{fmt::Display, fs, io, path::Path, str::FromStr};
 
+use crate::style;
+use crate::template::benchmark_chart;
 use crate::Day;
 
 static MARKER: &str = "<!--- benchmarking table --->";
+static CHART_PATH: &str = "./data/benchmarks/chart.svg";
 
 #[derive(Debug)]
 pub enum Error {
@@ -18,14 +21,114 @@ impl From<std::io::Error> for Error {
     }
 }
 
-#[derive(Clone)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct Timings {
     pub day: Day,
     pub part_1: Option<String>,
     pub part_2: Option<String>,
+    pub part_1_nanos: Option<f64>,
+    pub part_2_nanos: Option<f64>,
     pub total_nanos: f64,
 }
 
+impl Timings {
+    /// Creates [`Timings`] from the formatted durations of both parts, e.g. `74.13µs`.
+    /// Styling is stripped, so that it never ends up in the readme.
+    #[must_use]
+    pub fn from_durations(day: Day, part_1: Option<String>, part_2: Option<String>) -> Self {
+        let part_1 = part_1.map(|s| style::strip(&s));
+        let part_2 = part_2.map(|s| style::strip(&s));
+        let part_1_nanos = part_1.as_deref().and_then(parse_duration);
+        let part_2_nanos = part_2.as_deref().and_then(parse_duration);
+
+        Self {
+            day,
+            part_1,
+            part_2,
+            part_1_nanos,
+            part_2_nanos,
+            total_nanos: part_1_nanos.unwrap_or(0.0) + part_2_nanos.unwrap_or(0.0),
+        }
+    }
+}
+
+/// Order of the rows in the benchmarking table.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SortOrder {
+    /// Ascending by day.
+    #[default]
+    Day,
+    /// Descending by total execution time, slowest day first.
+    Time,
+}
+
+impl FromStr for SortOrder {
+    type Err = SortOrderFromStrError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "day" => Ok(Self::Day),
+            "time" => Ok(Self::Time),
+            _ => Err(SortOrderFromStrError),
+        }
+    }
+}
+
+/// An error which can be returned when parsing a [`SortOrder`].
+#[derive(Debug)]
+pub struct SortOrderFromStrError;
+
+impl std::error::Error for SortOrderFromStrError {}
+
+impl Display for SortOrderFromStrError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("expecting a sort order of `day` or `time`")
+    }
+}
+
+/// A row of the benchmarking table.
+/// Rows are either measured in the current run or carried over from the previous table.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TableRow {
+    pub timings: Timings,
+    pub delta: Delta,
+}
+
+/// The change of a day's total time relative to the previous table.
+/// Rows that are carried over keep the delta they were written with.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Delta {
+    /// The day was not in the previous table.
+    New,
+    /// The change in percent.
+    Change(f64),
+    /// Neither timing of the previous table is known.
+    Unknown,
+}
+
+impl Display for Delta {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Delta::New => f.write_str("new"),
+            Delta::Change(percent) => write!(f, "{percent:+.1}%"),
+            Delta::Unknown => f.write_str("-"),
+        }
+    }
+}
+
+impl Delta {
+    /// Parses a cell written by [`Delta`]'s `Display` impl.
+    fn parse(cell: &str) -> Self {
+        match cell.trim() {
+            "new" => Delta::New,
+            cell => cell
+                .strip_suffix('%')
+                .and_then(|percent| percent.parse().ok())
+                .map_or(Delta::Unknown, Delta::Change),
+        }
+    }
+}
+
 pub struct TablePosition {
     pos_start: usize,
     pos_end: usize,
@@ -36,6 +139,21 @@ pub fn get_path_for_bin(day: Day) -> String {
     format!("./src/bin/{day}.rs")
 }
 
+fn parse_to_float(s: &str, postfix: &str) -> Option<f64> {
+    s.split(postfix).next()?.parse().ok()
+}
+
+/// Parses a duration formatted by [`std::time::Duration`]'s `Debug` impl into nanoseconds.
+pub(crate) fn parse_duration(s: &str) -> Option<f64> {
+    // for possible time formats, see: https://github.com/rust-lang/rust/blob/1.64.0/library/core/src/time.rs#L1176-L1200
+    match s.trim() {
+        s if s.contains("ns") => s.split("ns").next()?.parse::<f64>().ok(),
+        s if s.contains("µs") => parse_to_float(s, "µs").map(|x| x * 1000_f64),
+        s if s.contains("ms") => parse_to_float(s, "ms").map(|x| x * 1_000_000_f64),
+        s => parse_to_float(s, "s").map(|x| x * 1_000_000_000_f64),
+    }
+}
+
 fn locate_table(readme: &str) -> Result<TablePosition, Error> {
     let matches: Vec<_> = readme.match_indices(MARKER).collect();
 
@@ -58,104 +176,224 @@ fn locate_table(readme: &str) -> Result<TablePosition, Error> {
     Ok(TablePosition { pos_start, pos_end })
 }
 
-fn construct_table(prefix: &str, timings: Vec<Timings>, total_millis: f64) -> String {
+fn parse_cell(cell: &str) -> Option<String> {
+    match cell.trim().trim_matches('`') {
+        "-" | "" => None,
+        s => Some(s.to_string()),
+    }
+}
+
+/// Parses a row of the table. Tables written before the Delta column existed have no delta.
+fn parse_row(line: &str) -> Option<TableRow> {
+    let mut cells = line.trim().trim_matches('|').split('|');
+
+    let day = cells
+        .next()?
+        .trim()
+        .strip_prefix("[Day ")?
+        .split(']')
+        .next()?
+        .parse::<u8>()
+        .ok()
+        .and_then(Day::new)?;
+
+    let part_1 = parse_cell(cells.next()?);
+    let part_2 = parse_cell(cells.next()?);
+    // the share is recomputed from the timings.
+    let delta = cells.nth(1).map_or(Delta::Unknown, Delta::parse);
+
+    Some(TableRow {
+        timings: Timings::from_durations(day, part_1, part_2),
+        delta,
+    })
+}
+
+/// Parses the rows of the benchmarking table that is currently in the readme.
+fn parse_table(readme: &str) -> Result<Vec<TableRow>, Error> {
+    let positions = locate_table(readme)?;
+
+    Ok(readme[positions.pos_start..positions.pos_end]
+        .lines()
+        .filter(|line| line.starts_with("| [Day "))
+        .filter_map(parse_row)
+        .collect())
+}
+
+/// Merges the timings of the current run into the previous table.
+/// Days that were not part of the current run keep their previous row, including its delta.
+fn merge_rows(previous: Vec<TableRow>, current: Vec<Timings>, sort: SortOrder) -> Vec<TableRow> {
+    let mut rows: Vec<TableRow> = current
+        .into_iter()
+        .map(|timings| {
+            let previous_nanos = previous
+                .iter()
+                .find(|row| row.timings.day == timings.day)
+                .map(|row| row.timings.total_nanos);
+            TableRow {
+                delta: delta(timings.total_nanos, previous_nanos),
+                timings,
+            }
+        })
+        .collect();
+
+    for row in previous {
+        if !rows.iter().any(|x| x.timings.day == row.timings.day) {
+            rows.push(row);
+        }
+    }
+
+    match sort {
+        SortOrder::Day => rows.sort_by_key(|row| row.timings.day),
+        SortOrder::Time => rows.sort_by(|a, b| {
+            b.timings
+                .total_nanos
+                .total_cmp(&a.timings.total_nanos)
+                .then(a.timings.day.cmp(&b.timings.day))
+        }),
+    }
+
+    rows
+}
+
+fn format_share(nanos: f64, total_nanos: f64) -> String {
+    if total_nanos > 0.0 {
+        format!("{:.1}%", nanos / total_nanos * 100.0)
+    } else {
+        "-".into()
+    }
+}
+
+fn delta(nanos: f64, previous_nanos: Option<f64>) -> Delta {
+    match previous_nanos {
+        Some(previous) if previous > 0.0 => Delta::Change((nanos - previous) / previous * 100.0),
+        Some(_) => Delta::Unknown,
+        None => Delta::New,
+    }
+}
+
+fn construct_table(prefix: &str, rows: &[TableRow], chart_path: Option<&str>) -> String {
     let header = format!("{prefix} Benchmarks");
+    let total_nanos: f64 = rows.iter().map(|row| row.timings.total_nanos).sum();
+    let total_millis = total_nanos / 1_000_000_f64;
 
     let mut lines: Vec<String> = vec![
         MARKER.into(),
         header,
         String::new(),
-        "| Day | Part 1 | Part 2 |".into(),
-        "| :---: | :---: | :---:  |".into(),
+        "| Day | Part 1 | Part 2 | Share | Delta |".into(),
+        "| :---: | :---: | :---: | :---: | :---: |".into(),
     ];
 
-    for timing in timings {
+    for row in rows {
+        let timing = &row.timings;
         let path = get_path_for_bin(timing.day);
         lines.push(format!(
-            "| [Day {}]({}) | `{}` | `{}` |",
+            "| [Day {}]({}) | `{}` | `{}` | {} | {} |",
             timing.day.into_inner(),
             path,
-            timing.part_1.unwrap_or_else(|| "-".into()),
-            timing.part_2.unwrap_or_else(|| "-".into())
+            timing.part_1.as_deref().unwrap_or("-"),
+            timing.part_2.as_deref().unwrap_or("-"),
+            format_share(timing.total_nanos, total_nanos),
+            row.delta
         ));
     }
 
     lines.push(String::new());
     lines.push(format!("**Total: {total_millis:.2}ms**"));
+
+    if let Some(chart_path) = chart_path {
+        lines.push(String::new());
+        lines.push(format!("![Benchmark chart]({chart_path})"));
+    }
+
     lines.push(MARKER.into());
 
     lines.join("\n")
 }
 
-fn update_content(s: &mut String, timings: Vec<Timings>, total_millis: f64) -> Result<(), Error> {
+fn update_content(
+    s: &mut String,
+    rows: &[TableRow],
+    chart_path: Option<&str>,
+) -> Result<(), Error> {
     let positions = locate_table(s)?;
-    let table = construct_table("##", timings, total_millis);
+    let table = construct_table("##", rows, chart_path);
     s.replace_range(positions.pos_start..positions.pos_end, &table);
     Ok(())
 }
 
-pub fn update(timings: Vec<Timings>, total_millis: f64) -> Result<(), Error> {
+/// Writes the benchmark chart to `data/benchmarks`. Returns the chart path if a chart was written.
+fn write_chart(rows: &[TableRow]) -> Result<Option<&'static str>, Error> {
+    let timings: Vec<Timings> = rows.iter().map(|row| row.timings.clone()).collect();
+
+    let Some(svg) = benchmark_chart::render_svg(&timings) else {
+        return Ok(None);
+    };
+
+    if let Some(dir) = Path::new(CHART_PATH).parent() {
+        fs::create_dir_all(dir)?;
+    }
+
+    fs::write(CHART_PATH, svg)?;
+    Ok(Some(CHART_PATH))
+}
+
+pub fn update(timings: Vec<Timings>, sort: SortOrder) -> Result<(), Error> {
     let path = "README.md";
     let mut readme = String::from_utf8_lossy(&fs::read(path)?).to_string();
-    update_content(&mut readme, timings, total_millis)?;
+    let rows = merge_rows(parse_table(&readme)?, timings, sort);
+    let chart_path = write_chart(&rows)?;
+    update_content(&mut readme, &rows, chart_path)?;
     fs::write(path, &readme)?;
     Ok(())
 }
 
 #[cfg(feature = "test_lib")]
 mod tests {
-    use super::{update_content, Timings, MARKER};
+    use super::{
+        merge_rows, parse_table, update_content, Error, SortOrder, Timings, CHART_PATH, MARKER,
+    };
     use crate::day;
 
     fn get_mock_timings() -> Vec<Timings> {
         vec![
-            Timings {
-                day: day!(1),
-                part_1: Some("10ms".into()),
-                part_2: Some("20ms".into()),
-                total_nanos: 3e+10,
-            },
-            Timings {
-                day: day!(2),
-                part_1: Some("30ms".into()),
-                part_2: Some("40ms".into()),
-                total_nanos: 7e+10,
-            },
-            Timings {
-                day: day!(4),
-                part_1: Some("40ms".into()),
-                part_2: Some("50ms".into()),
-                total_nanos: 9e+10,
-            },
+            Timings::from_durations(day!(1), Some("10ms".into()), Some("20ms".into())),
+            Timings::from_durations(day!(2), Some("30ms".into()), Some("40ms".into())),
+            Timings::from_durations(day!(4), Some("40ms".into()), Some("50ms".into())),
         ]
     }
 
+    fn update_with(s: &mut String, timings: Vec<Timings>, sort: SortOrder) -> Result<(), Error> {
+        let rows = merge_rows(parse_table(s)?, timings, sort);
+        update_content(s, &rows, None)
+    }
+
     #[test]
     #[should_panic]
     fn errors_if_marker_not_present() {
         let mut s = "# readme".to_string();
-        update_content(&mut s, get_mock_timings(), 190.0).unwrap();
+        update_with(&mut s, get_mock_timings(), SortOrder::Day).unwrap();
     }
 
     #[test]
     #[should_panic]
     fn errors_if_too_many_markers_present() {
         let mut s = format!("{} {} {}", MARKER, MARKER, MARKER);
-        update_content(&mut s, get_mock_timings(), 190.0).unwrap();
+        update_with(&mut s, get_mock_timings(), SortOrder::Day).unwrap();
     }
 
     #[test]
     fn updates_empty_benchmarks() {
         let mut s = format!("foo\nbar\n{}{}\nbaz", MARKER, MARKER);
-        update_content(&mut s, get_mock_timings(), 190.0).unwrap();
+        update_with(&mut s, get_mock_timings(), SortOrder::Day).unwrap();
         assert_eq!(s.contains("## Benchmarks"), true);
     }
 
     #[test]
     fn updates_existing_benchmarks() {
         let mut s = format!("foo\nbar\n{}{}\nbaz", MARKER, MARKER);
-        update_content(&mut s, get_mock_timings(), 190.0).unwrap();
-        update_content(&mut s, get_mock_timings(), 190.0).unwrap();
+        update_with(&mut s, get_mock_timings(), SortOrder::Day).unwrap();
+        update_with(&mut s, get_mock_timings(), SortOrder::Day).unwrap();
         assert_eq!(s.matches(MARKER).collect::<Vec<&str>>().len(), 2);
         assert_eq!(s.matches("## Benchmarks").collect::<Vec<&str>>().len(), 1);
     }
@@ -163,18 +401,18 @@ mod tests {
     #[test]
     fn format_benchmarks() {
         let mut s = format!("foo\nbar\n{}\n{}\nbaz", MARKER, MARKER);
-        update_content(&mut s, get_mock_timings(), 190.0).unwrap();
+        update_with(&mut s, get_mock_timings(), SortOrder::Day).unwrap();
         let expected = [
             "foo",
             "bar",
             "<!--- benchmarking table --->",
             "## Benchmarks",
             "",
-            "| Day | Part 1 | Part 2 |",
-            "| :---: | :---: | :---:  |",
-            "| [Day 1](./src/bin/01.rs) | `10ms` | `20ms` |",
-            "| [Day 2](./src/bin/02.rs) | `30ms` | `40ms` |",
-            "| [Day 4](./src/bin/04.rs) | `40ms` | `50ms` |",
+            "| Day | Part 1 | Part 2 | Share | Delta |",
+            "| :---: | :---: | :---: | :---: | :---: |",
+            "| [Day 1](./src/bin/01.rs) | `10ms` | `20ms` | 15.8% | new |",
+            "| [Day 2](./src/bin/02.rs) | `30ms` | `40ms` | 36.8% | new |",
+            "| [Day 4](./src/bin/04.rs) | `40ms` | `50ms` | 47.4% | new |",
             "",
             "**Total: 190.00ms**",
             "<!--- benchmarking table --->",
@@ -183,4 +421,84 @@ mod tests {
         .join("\n");
         assert_eq!(s, expected);
     }
+
+    #[test]
+    fn round_trips_table() {
+        let mut s = format!("foo\n{}\n{}\nbaz", MARKER, MARKER);
+        update_with(&mut s, get_mock_timings(), SortOrder::Day).unwrap();
+        let timings: Vec<Timings> = parse_table(&s)
+            .unwrap()
+            .into_iter()
+            .map(|row| row.timings)
+            .collect();
+        assert_eq!(timings, get_mock_timings());
+
+        // Carried-over rows keep their delta, whether it is new, a change or unknown.
+        update_with(
+            &mut s,
+            vec![
+                Timings::from_durations(day!(1), Some("12ms".into()), Some("20ms".into())),
+                Timings::from_durations(day!(2), Some("29ms".into()), Some("40ms".into())),
+                Timings::from_durations(day!(3), Some("5ms".into()), None),
+            ],
+            SortOrder::Day,
+        )
+        .unwrap();
+        let before = s.clone();
+        update_with(&mut s, vec![], SortOrder::Day).unwrap();
+        assert_eq!(s, before);
+        update_with(&mut s, vec![], SortOrder::Time).unwrap();
+        update_with(&mut s, vec![], SortOrder::Day).unwrap();
+        assert_eq!(s, before);
+    }
+
+    #[test]
+    fn merges_with_previous_benchmarks() {
+        let mut s = format!("foo\n{}\n{}\nbaz", MARKER, MARKER);
+        update_with(&mut s, get_mock_timings(), SortOrder::Day).unwrap();
+        update_with(
+            &mut s,
+            vec![
+                Timings::from_durations(day!(2), Some("15ms".into()), Some("20ms".into())),
+                Timings::from_durations(day!(3), Some("5ms".into()), None),
+            ],
+            SortOrder::Day,
+        )
+        .unwrap();
+
+        assert!(s.contains("| [Day 1](./src/bin/01.rs) | `10ms` | `20ms` | 18.8% | new |"));
+        assert!(s.contains("| [Day 2](./src/bin/02.rs) | `15ms` | `20ms` | 21.9% | -50.0% |"));
+        assert!(s.contains("| [Day 3](./src/bin/03.rs) | `5ms` | `-` | 3.1% | new |"));
+        assert!(s.contains("| [Day 4](./src/bin/04.rs) | `40ms` | `50ms` | 56.2% | new |"));
+        assert!(s.contains("**Total: 160.00ms**"));
+    }
+
+    #[test]
+    fn sorts_benchmarks_by_time() {
+        let mut s = format!("foo\n{}\n{}\nbaz", MARKER, MARKER);
+        update_with(&mut s, get_mock_timings(), SortOrder::Time).unwrap();
+        let days: Vec<&str> = s
+            .lines()
+            .filter_map(|line| line.strip_prefix("| [Day "))
+            .map(|line| &line[..1])
+            .collect();
+        assert_eq!(days, ["4", "2", "1"]);
+    }
+
+    #[test]
+    fn parses_sort_order() {
+        assert_eq!("day".parse::<SortOrder>().unwrap(), SortOrder::Day);
+        assert_eq!("time".parse::<SortOrder>().unwrap(), SortOrder::Time);
+        assert!("speed".parse::<SortOrder>().is_err());
+    }
+
+    #[test]
+    fn embeds_benchmark_chart() {
+        let mut s = format!("foo\n{}\n{}\nbaz", MARKER, MARKER);
+        let rows = merge_rows(vec![], get_mock_timings(), SortOrder::Day);
+        update_content(&mut s, &rows, Some(CHART_PATH)).unwrap();
+        assert!(s.contains(
+            "**Total: 190.00ms**\n\n![Benchmark chart](./data/benchmarks/chart.svg)\n<!--- benchmarking table --->"
+        ));
+    }
 }
@@ -0,0 +1,457 @@
+/// Module that exports the results of `cargo all` as machine readable reports.
+use std::{fmt::Display, fs, io, path::Path, str::FromStr};
+
+use crate::template::ledger::Answers;
+use crate::Day;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Csv,
+    Json,
+    Junit,
+}
+
+impl Format {
+    /// Guesses the report format from the extension of `path`.
+    #[must_use]
+    pub fn from_path(path: &str) -> Option<Self> {
+        match Path::new(path).extension()?.to_str()? {
+            "csv" => Some(Self::Csv),
+            "json" => Some(Self::Json),
+            "xml" => Some(Self::Junit),
+            _ => None,
+        }
+    }
+}
+
+impl FromStr for Format {
+    type Err = FormatFromStrError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "csv" => Ok(Self::Csv),
+            "json" => Ok(Self::Json),
+            "junit" => Ok(Self::Junit),
+            _ => Err(FormatFromStrError),
+        }
+    }
+}
+
+/// An error which can be returned when parsing a [`Format`].
+#[derive(Debug)]
+pub struct FormatFromStrError;
+
+impl std::error::Error for FormatFromStrError {}
+
+impl Display for FormatFromStrError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("expecting a report format of `csv`, `json` or `junit`")
+    }
+}
+
+/// What a solution binary printed for one part.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PartOutput {
+    /// The part returned an answer.
+    Answer {
+        answer: String,
+        duration: Option<String>,
+        nanos: Option<f64>,
+    },
+    /// The part returned [`None`].
+    Unsolved,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    /// The answer matches the answer ledger.
+    Passed,
+    /// The answer differs from the answer ledger.
+    Failed,
+    /// The part returned an answer, but the ledger does not know the correct one.
+    Unchecked,
+    /// The part returned [`None`].
+    Unsolved,
+    /// The binary did not print a result for this part, e.g. because it crashed.
+    Missing,
+}
+
+impl Display for Status {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Status::Passed => "passed",
+            Status::Failed => "failed",
+            Status::Unchecked => "unchecked",
+            Status::Unsolved => "unsolved",
+            Status::Missing => "missing",
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PartReport {
+    pub part: u8,
+    pub answer: Option<String>,
+    pub expected: Option<String>,
+    pub duration: Option<String>,
+    pub nanos: Option<f64>,
+    pub status: Status,
+}
+
+impl PartReport {
+    #[must_use]
+    pub fn new(part: u8, output: Option<PartOutput>, expected: Option<&str>) -> Self {
+        let expected = expected.map(ToString::to_string);
+
+        match output {
+            Some(PartOutput::Answer {
+                answer,
+                duration,
+                nanos,
+            }) => {
+                let status = match &expected {
+                    Some(expected) if *expected == answer => Status::Passed,
+                    Some(_) => Status::Failed,
+                    None => Status::Unchecked,
+                };
+                Self {
+                    part,
+                    answer: Some(answer),
+                    expected,
+                    duration,
+                    nanos,
+                    status,
+                }
+            }
+            Some(PartOutput::Unsolved) => Self {
+                part,
+                answer: None,
+                expected,
+                duration: None,
+                nanos: None,
+                status: Status::Unsolved,
+            },
+            None => Self {
+                part,
+                answer: None,
+                expected,
+                duration: None,
+                nanos: None,
+                status: Status::Missing,
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DayReport {
+    pub day: Day,
+    pub parts: Vec<PartReport>,
+}
+
+impl DayReport {
+    /// Builds the report of a day from the parsed output of its binary and its ledger entry.
+    #[must_use]
+    pub fn new(day: Day, outputs: [Option<PartOutput>; 2], answers: &Answers) -> Self {
+        let parts = outputs
+            .into_iter()
+            .zip(1..)
+            .map(|(output, part)| PartReport::new(part, output, answers.get(part)))
+            .collect();
+
+        Self { day, parts }
+    }
+}
+
+fn escape_csv(s: &str) -> String {
+    if s.contains([',', '"', '\n']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+fn escape_json(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len() + 2);
+    escaped.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if c.is_control() => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped.push('"');
+    escaped
+}
+
+fn escape_xml(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn json_string_or_null(s: Option<&str>) -> String {
+    s.map_or_else(|| "null".into(), escape_json)
+}
+
+fn render_csv(reports: &[DayReport]) -> String {
+    let mut lines = vec!["day,part,answer,expected,status,duration,nanos".to_string()];
+
+    for report in reports {
+        for part in &report.parts {
+            lines.push(format!(
+                "{},{},{},{},{},{},{}",
+                report.day,
+                part.part,
+                escape_csv(part.answer.as_deref().unwrap_or_default()),
+                escape_csv(part.expected.as_deref().unwrap_or_default()),
+                part.status,
+                part.duration.as_deref().unwrap_or_default(),
+                part.nanos.map(|x| x.to_string()).unwrap_or_default()
+            ));
+        }
+    }
+
+    lines.push(String::new());
+    lines.join("\n")
+}
+
+fn render_json(reports: &[DayReport]) -> String {
+    let days: Vec<String> = reports
+        .iter()
+        .map(|report| {
+            let parts: Vec<String> = report
+                .parts
+                .iter()
+                .map(|part| {
+                    format!(
+                        r#"{{"part":{},"answer":{},"expected":{},"status":"{}","duration":{},"nanos":{}}}"#,
+                        part.part,
+                        json_string_or_null(part.answer.as_deref()),
+                        json_string_or_null(part.expected.as_deref()),
+                        part.status,
+                        json_string_or_null(part.duration.as_deref()),
+                        part.nanos.map_or_else(|| "null".into(), |x| x.to_string())
+                    )
+                })
+                .collect();
+
+            format!(
+                r#"{{"day":{},"parts":[{}]}}"#,
+                report.day.into_inner(),
+                parts.join(",")
+            )
+        })
+        .collect();
+
+    let total_nanos: f64 = reports
+        .iter()
+        .flat_map(|report| &report.parts)
+        .filter_map(|part| part.nanos)
+        .sum();
+
+    format!(
+        "{{\"days\":[{}],\"total_nanos\":{total_nanos}}}\n",
+        days.join(",")
+    )
+}
+
+fn render_junit(reports: &[DayReport]) -> String {
+    let parts = || reports.iter().flat_map(|report| &report.parts);
+    let count = |status: Status| parts().filter(|part| part.status == status).count();
+    let total_secs: f64 = parts().filter_map(|part| part.nanos).sum::<f64>() / 1e9;
+
+    let mut lines = vec![
+        r#"<?xml version="1.0" encoding="UTF-8"?>"#.to_string(),
+        format!(
+            r#"<testsuites name="advent_of_code" tests="{}" failures="{}" errors="{}" skipped="{}" time="{total_secs:.9}">"#,
+            parts().count(),
+            count(Status::Failed),
+            count(Status::Missing),
+            count(Status::Unsolved)
+        ),
+    ];
+
+    for report in reports {
+        let secs: f64 = report
+            .parts
+            .iter()
+            .filter_map(|part| part.nanos)
+            .sum::<f64>()
+            / 1e9;
+        let count = |status: Status| {
+            report
+                .parts
+                .iter()
+                .filter(|part| part.status == status)
+                .count()
+        };
+
+        lines.push(format!(
+            r#"  <testsuite name="day {}" tests="{}" failures="{}" errors="{}" skipped="{}" time="{secs:.9}">"#,
+            report.day,
+            report.parts.len(),
+            count(Status::Failed),
+            count(Status::Missing),
+            count(Status::Unsolved)
+        ));
+
+        for part in &report.parts {
+            let secs = part.nanos.unwrap_or(0.0) / 1e9;
+            let open = format!(
+                r#"    <testcase classname="day{}" name="part {}" time="{secs:.9}""#,
+                report.day, part.part
+            );
+
+            let body = match part.status {
+                Status::Passed | Status::Unchecked => None,
+                Status::Failed => Some(format!(
+                    r#"<failure message="expected {}, got {}"/>"#,
+                    escape_xml(part.expected.as_deref().unwrap_or_default()),
+                    escape_xml(part.answer.as_deref().unwrap_or_default())
+                )),
+                Status::Unsolved => Some(r#"<skipped message="not solved"/>"#.to_string()),
+                Status::Missing => Some(r#"<error message="no result printed"/>"#.to_string()),
+            };
+
+            match body {
+                Some(body) => {
+                    lines.push(format!("{open}>"));
+                    lines.push(format!("      {body}"));
+                    lines.push("    </testcase>".into());
+                }
+                None => lines.push(format!("{open}/>")),
+            }
+        }
+
+        lines.push("  </testsuite>".into());
+    }
+
+    lines.push("</testsuites>".into());
+    lines.push(String::new());
+    lines.join("\n")
+}
+
+/// Renders the reports in the given format.
+#[must_use]
+pub fn render(reports: &[DayReport], format: Format) -> String {
+    match format {
+        Format::Csv => render_csv(reports),
+        Format::Json => render_json(reports),
+        Format::Junit => render_junit(reports),
+    }
+}
+
+/// Writes the reports to `path` in the given format.
+pub fn write(path: &str, format: Format, reports: &[DayReport]) -> Result<(), io::Error> {
+    if let Some(dir) = Path::new(path).parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(path, render(reports, format))
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{render, DayReport, Format, PartOutput, Status};
+    use crate::day;
+    use crate::template::ledger::Answers;
+
+    fn answer(answer: &str, duration: &str, nanos: f64) -> Option<PartOutput> {
+        Some(PartOutput::Answer {
+            answer: answer.into(),
+            duration: Some(duration.into()),
+            nanos: Some(nanos),
+        })
+    }
+
+    fn get_mock_reports() -> Vec<DayReport> {
+        vec![
+            DayReport::new(
+                day!(1),
+                [
+                    answer("142", "1.5µs", 1500.0),
+                    answer("28,1", "2.0µs", 2000.0),
+                ],
+                &Answers::parse("142\n"),
+            ),
+            DayReport::new(
+                day!(2),
+                [answer("8", "1.0ms", 1e6), Some(PartOutput::Unsolved)],
+                &Answers::parse("9\n"),
+            ),
+            DayReport::new(day!(3), [None, None], &Answers::default()),
+        ]
+    }
+
+    #[test]
+    fn derives_status_from_ledger() {
+        let statuses: Vec<Status> = get_mock_reports()
+            .iter()
+            .flat_map(|report| report.parts.iter().map(|part| part.status))
+            .collect();
+        assert_eq!(
+            statuses,
+            [
+                Status::Passed,
+                Status::Unchecked,
+                Status::Failed,
+                Status::Unsolved,
+                Status::Missing,
+                Status::Missing
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_format() {
+        assert_eq!("junit".parse::<Format>().unwrap(), Format::Junit);
+        assert!("yaml".parse::<Format>().is_err());
+        assert_eq!(Format::from_path("out/report.xml"), Some(Format::Junit));
+        assert_eq!(Format::from_path("report.csv"), Some(Format::Csv));
+        assert_eq!(Format::from_path("report"), None);
+    }
+
+    #[test]
+    fn renders_csv() {
+        let csv = render(&get_mock_reports(), Format::Csv);
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines[0], "day,part,answer,expected,status,duration,nanos");
+        assert_eq!(lines[1], "01,1,142,142,passed,1.5µs,1500");
+        assert_eq!(lines[2], "01,2,\"28,1\",,unchecked,2.0µs,2000");
+        assert_eq!(lines[3], "02,1,8,9,failed,1.0ms,1000000");
+        assert_eq!(lines[4], "02,2,,,unsolved,,");
+        assert_eq!(lines.len(), 7);
+    }
+
+    #[test]
+    fn renders_json() {
+        let json = render(&get_mock_reports()[1..2], Format::Json);
+        assert_eq!(
+            json.trim_end(),
+            concat!(
+                r#"{"days":[{"day":2,"parts":["#,
+                r#"{"part":1,"answer":"8","expected":"9","status":"failed","duration":"1.0ms","nanos":1000000},"#,
+                r#"{"part":2,"answer":null,"expected":null,"status":"unsolved","duration":null,"nanos":null}"#,
+                r#"]}],"total_nanos":1000000}"#
+            )
+        );
+    }
+
+    #[test]
+    fn renders_junit() {
+        let xml = render(&get_mock_reports(), Format::Junit);
+        assert!(xml.starts_with(r#"<?xml version="1.0" encoding="UTF-8"?>"#));
+        assert!(xml.contains(r#"tests="6" failures="1" errors="2" skipped="1""#));
+        assert!(xml.contains(r#"<testcase classname="day01" name="part 1" time="0.000001500"/>"#));
+        assert!(xml.contains(r#"<failure message="expected 9, got 8"/>"#));
+        assert!(xml.contains(r#"<skipped message="not solved"/>"#));
+        assert!(xml.contains(r#"<error message="no result printed"/>"#));
+        assert_eq!(xml.matches("<testsuite ").count(), 3);
+    }
+}
@@ -1,24 +1,340 @@
 /// Encapsulates code that interacts with solution functions.
-use crate::template::{aoc_cli, ANSI_ITALIC, ANSI_RESET};
+use crate::generate::{Generator, Implementation, Size};
+use crate::shrink::shrink;
+use crate::style::{bold, italic, Colour};
+use crate::template::{aoc_cli, input, ledger, Error, EXIT_USAGE};
+use crate::viz::{self, Table};
 use crate::Day;
 use std::fmt::Display;
 use std::io::{stdout, Write};
-use std::process::Output;
+use std::panic::{self, AssertUnwindSafe};
 use std::time::{Duration, Instant};
-use std::{cmp, env, process};
+use std::{cmp, env, fs, process};
 
-use super::ANSI_BOLD;
+/// Read the puzzle input for a solution, or stdin if it was started with `--stdin`.
+/// Problems with the input file are printed as warnings, passing `--normalize` fixes line endings
+/// and the trailing newline before the solution sees the input.
+/// Exits the process if the input can not be read.
+pub fn read_input(day: Day) -> String {
+    let input = if env::args().any(|x| x == "--stdin") {
+        input::load_stdin(day, is_normalized())
+    } else {
+        input::load("inputs", day, is_normalized())
+    };
+
+    match input {
+        Ok(input) => take_input(input),
+        Err(e) => {
+            eprintln!("Error: {e}");
+            process::exit(1);
+        }
+    }
+}
+
+/// Prepare a puzzle input that was embedded into the binary by the `embed_inputs` feature, or
+/// read stdin instead if the solution was started with `--stdin`.
+/// Problems with the input are reported the same way as in [`read_input`].
+pub fn read_embedded_input(day: Day, text: &str) -> String {
+    if env::args().any(|x| x == "--stdin") {
+        return read_input(day);
+    }
+
+    take_input(input::Input::new(
+        text.to_string(),
+        "inputs",
+        day,
+        is_normalized(),
+    ))
+}
+
+fn is_normalized() -> bool {
+    env::args().any(|x| x == "--normalize")
+}
+
+fn take_input(input: input::Input) -> String {
+    for warning in &input.warnings {
+        eprintln!("Warning: {warning}");
+    }
+    input.text
+}
+
+/// If the solution was started with `--generate`, prints the input generated for `--seed <n>`
+/// and `--size small|medium|large` and exits, see `cargo gen`.
+pub fn run_generator(day: Day, generate: Option<Generator>) {
+    let args: Vec<String> = env::args().collect();
+    if !args.iter().any(|x| x == "--generate") {
+        return;
+    }
+
+    let Some(generate) = generate else {
+        eprintln!("Error: day {day} has no input generator, register one with `solution!({}, generate = generate)`.", day.into_inner());
+        process::exit(EXIT_USAGE);
+    };
+
+    let value = |name: &str| {
+        let index = args.iter().position(|x| x == name)?;
+        args.get(index + 1)
+    };
+
+    let seed = value("--seed").map_or(Ok(0), |s| s.parse::<u64>().map_err(|e| e.to_string()));
+    let size = value("--size").map_or(Ok(Size::default()), |s| s.parse::<Size>());
+
+    match (seed, size) {
+        (Ok(seed), Ok(size)) => {
+            print!("{}", generate(seed, size));
+            process::exit(0);
+        }
+        (Err(e), _) | (_, Err(e)) => {
+            eprintln!("Error: {e}");
+            process::exit(EXIT_USAGE);
+        }
+    }
+}
+
+/// Returns whether the solution was started with `--variants`, see `cargo solve --variants`.
+/// Exits if none of its parts has variants.
+pub fn variants_requested(day: Day, parts: &[u8]) -> bool {
+    if !env::args().any(|x| x == "--variants") {
+        return false;
+    }
+
+    if parts.is_empty() {
+        eprintln!("Error: day {day} has no variants, register them with `solution!({}, variants = [2 => [part_two_bruteforce]])`.", day.into_inner());
+        process::exit(EXIT_USAGE);
+    }
+    true
+}
+
+/// Runs and benchmarks all implementations of a part, then prints a comparison table.
+/// Exits with an error if they do not all return the same answer.
+pub fn run_variants<T: Display + PartialEq>(
+    input: &str,
+    part: u8,
+    implementations: &[Implementation<T>],
+) {
+    print!(
+        "{}: {}",
+        bold(format!("Part {part}")),
+        italic(format!(
+            "benching {} implementations",
+            implementations.len()
+        ))
+    );
+    let _ = stdout().flush();
+
+    let runs: Vec<(&str, Option<T>, Duration, u128)> = implementations
+        .iter()
+        .map(|&(name, func)| {
+            let timer = Instant::now();
+            let answer = func(input);
+            let (duration, samples) = bench(func, input, &timer.elapsed());
+            (name, answer, duration, samples)
+        })
+        .collect();
+
+    let fastest = runs
+        .iter()
+        .map(|&(_, _, duration, _)| duration)
+        .min()
+        .unwrap_or_default();
+    let expected = &runs[0].1;
+
+    let mut table = Table::new(["Implementation", "Answer", "Time", "Samples", "Relative"]);
+    for (name, answer, duration, samples) in &runs {
+        let relative = if fastest.is_zero() {
+            "-".to_string()
+        } else {
+            format!("{:.2}×", duration.as_secs_f64() / fastest.as_secs_f64())
+        };
+        table.add_row(
+            [
+                (*name).to_string(),
+                answer.as_ref().map_or("✖".into(), ToString::to_string),
+                format!("{duration:.1?}"),
+                samples.to_string(),
+                relative,
+            ],
+            (answer != expected).then_some(Colour::Red),
+        );
+    }
+
+    print!("\r");
+    println!("{}", bold(format!("Part {part}")));
+    println!("{table}");
+
+    if runs.iter().any(|(_, answer, _, _)| answer != expected) {
+        eprintln!(
+            "Error: the implementations of part {part} disagree with {}.",
+            runs[0].0
+        );
+        process::exit(1);
+    }
+}
+
+/// Returns the part to shrink the input for if the solution was started with `--shrink --part <n>`,
+/// see `cargo shrink`.
+pub fn shrink_requested() -> Option<u8> {
+    let args: Vec<String> = env::args().collect();
+    if !args.iter().any(|x| x == "--shrink") {
+        return None;
+    }
+
+    let index = args.iter().position(|x| x == "--part")?;
+    match args.get(index + 1).map(|s| s.parse::<u8>()) {
+        Some(Ok(part @ (1 | 2))) => Some(part),
+        _ => {
+            eprintln!("Error: expecting 1 or 2 for `--part`.");
+            process::exit(EXIT_USAGE);
+        }
+    }
+}
+
+/// How the implementations of a part fail for an input.
+#[derive(Debug)]
+enum Failure {
+    Panic(String),
+    Disagreement {
+        answer: Option<String>,
+        reference: Option<String>,
+    },
+}
+
+impl Failure {
+    /// Whether `other` is the same failure, possibly with different answers.
+    fn reproduces(&self, other: &Failure) -> bool {
+        match (self, other) {
+            (Failure::Panic(a), Failure::Panic(b)) => a == b,
+            (Failure::Disagreement { .. }, Failure::Disagreement { .. }) => true,
+            _ => false,
+        }
+    }
+}
+
+/// Shrinks the input while the first implementation of a part keeps failing the same way against
+/// `--against <variant|ledger>`, then writes the result to `data/examples/DD-shrunk.txt` and exits.
+pub fn run_shrink<T: Display + PartialEq>(
+    day: Day,
+    input: &str,
+    part: u8,
+    implementations: &[Implementation<T>],
+) -> ! {
+    let args: Vec<String> = env::args().collect();
+    let against = args
+        .iter()
+        .position(|x| x == "--against")
+        .and_then(|i| args.get(i + 1))
+        .map_or("ledger", String::as_str);
+
+    let (name, func) = implementations[0];
+    let variant = implementations[1..].iter().find(|(v, _)| *v == against);
+    let expected = ledger::read(day).get(part).map(String::from);
+
+    if against == "ledger" && expected.is_none() {
+        eprintln!(
+            "Error: part {part} has no known answer in \"{}\".",
+            ledger::get_path(day)
+        );
+        process::exit(EXIT_USAGE);
+    }
+    if against != "ledger" && variant.is_none() {
+        let names: Vec<&str> = implementations[1..].iter().map(|(v, _)| *v).collect();
+        eprintln!(
+            "Error: part {part} has no variant \"{against}\", expecting ledger{}{}.",
+            if names.is_empty() { "" } else { " or " },
+            names.join(", ")
+        );
+        process::exit(EXIT_USAGE);
+    }
+
+    let run = |name: &str, func: fn(&str) -> Option<T>, input: &str| {
+        panic::catch_unwind(AssertUnwindSafe(|| func(input).map(|a| a.to_string())))
+            .map_err(|_| Failure::Panic(name.to_string()))
+    };
+    let check = |input: &str| -> Result<(), Failure> {
+        let answer = run(name, func, input)?;
+        let reference = match variant {
+            Some(&(variant, func)) => run(variant, func, input)?,
+            None => expected.clone(),
+        };
+        if answer == reference {
+            Ok(())
+        } else {
+            Err(Failure::Disagreement { answer, reference })
+        }
+    };
+
+    // Candidate inputs that panic would print their message.
+    let hook = panic::take_hook();
+    panic::set_hook(Box::new(|_| {}));
+    let failure = check(input);
+    let shrunk = match &failure {
+        Ok(()) => None,
+        Err(Failure::Disagreement { .. }) if variant.is_none() => None,
+        Err(failure) => {
+            println!(
+                "{}: {}",
+                bold(format!("Part {part}")),
+                italic(format!("shrinking {} lines", input.lines().count()))
+            );
+            Some(shrink(input, |candidate| {
+                check(candidate).is_err_and(|f| f.reproduces(failure))
+            }))
+        }
+    };
+    let shrunk_failure = shrunk.as_deref().map(check);
+    panic::set_hook(hook);
+
+    let Some(shrunk) = shrunk else {
+        if failure.is_ok() {
+            eprintln!("Error: {name} agrees with {against}, there is nothing to shrink.");
+            process::exit(1);
+        }
+        eprintln!("Error: {name} disagrees with the ledger, which only knows the answer to the full input. Register a reference implementation with `solution!({}, variants = [{part} => [...]])` and pass `--against <variant>` to shrink.", day.into_inner());
+        process::exit(EXIT_USAGE);
+    };
+
+    let show = |a: &Option<String>| a.clone().unwrap_or_else(|| "✖".into());
+    match shrunk_failure {
+        Some(Err(Failure::Panic(name))) => println!("{name} panics for:"),
+        Some(Err(Failure::Disagreement { answer, reference })) => println!(
+            "{name} = {} but {against} = {} for:",
+            bold(show(&answer)),
+            bold(show(&reference))
+        ),
+        _ => {}
+    }
+    print!("{shrunk}");
+
+    let path = format!("data/examples/{day}-shrunk.txt");
+    if let Err(e) = fs::write(&path, &shrunk) {
+        eprintln!("Error: could not write \"{path}\": {e}");
+        process::exit(1);
+    }
+    println!("Wrote the shrunk input to \"{path}\".");
+    process::exit(0);
+}
 
 pub fn run_part<I: Clone, T: Display>(func: impl Fn(I) -> Option<T>, input: I, day: Day, part: u8) {
     let part_str = format!("Part {part}");
 
-    let (result, duration, samples) =
-        run_timed(func, input, |result| print_result(result, &part_str, ""));
+    // Only the first run records frames, benchmark runs would just repeat them.
+    viz::set_recording(viz::Visualize::from_args().is_some());
+    let (result, duration, samples) = run_timed(func, input, |result| {
+        viz::set_recording(false);
+        print_result(result, &part_str, "");
+    });
 
     print_result(&result, &part_str, &format_duration(&duration, samples));
 
+    if let Err(e) = viz::finish(part) {
+        eprintln!("Warning: could not export frames: {e}");
+    }
+
     if let Some(result) = result {
-        submit_result(result, day, part);
+        if let Err(e) = submit_result(result, day, part) {
+            eprintln!("Error: {e}");
+            process::exit(e.exit_code());
+        }
     }
 }
 
@@ -37,6 +353,8 @@ fn run_timed<I: Clone, T>(
     hook(&result);
 
     let run = if std::env::args().any(|x| x == "--time") {
+        print!(" > {}", italic("benching"));
+        let _ = stdout().flush();
         bench(func, input, &base_time)
     } else {
         (base_time, 1)
@@ -46,18 +364,8 @@ fn run_timed<I: Clone, T>(
 }
 
 fn bench<I: Clone, T>(func: impl Fn(I) -> T, input: I, base_time: &Duration) -> (Duration, u128) {
-    let mut stdout = stdout();
-
-    print!(" > {ANSI_ITALIC}benching{ANSI_RESET}");
-    let _ = stdout.flush();
-
-    let bench_iterations = cmp::min(
-        10000,
-        cmp::max(
-            Duration::from_secs(1).as_nanos() / cmp::max(base_time.as_nanos(), 10),
-            10,
-        ),
-    );
+    let bench_iterations =
+        (Duration::from_secs(1).as_nanos() / cmp::max(base_time.as_nanos(), 10)).clamp(10, 10000);
 
     let mut timers: Vec<Duration> = vec![];
 
@@ -107,7 +415,7 @@ fn print_result<T: Display>(result: &Option<T>, part: &str, duration_str: &str)
                     println!("{result}");
                 }
             } else {
-                let str = format!("{part}: {ANSI_BOLD}{result}{ANSI_RESET}{duration_str}");
+                let str = format!("{part}: {}{duration_str}", bold(result));
                 if is_intermediate_result {
                     print!("{str}");
                 } else {
@@ -130,38 +438,27 @@ fn print_result<T: Display>(result: &Option<T>, part: &str, duration_str: &str)
 /// Parse the arguments passed to `solve` and try to submit one part of the solution if:
 ///  1. we are in `--release` mode.
 ///  2. aoc-cli is installed.
-fn submit_result<T: Display>(
-    result: T,
-    day: Day,
-    part: u8,
-) -> Option<Result<Output, aoc_cli::AocCommandError>> {
+fn submit_result<T: Display>(result: T, day: Day, part: u8) -> Result<(), Error> {
     let args: Vec<String> = env::args().collect();
 
     if !args.contains(&"--submit".into()) {
-        return None;
-    }
-
-    if args.len() < 3 {
-        eprintln!("Unexpected command-line input. Format: cargo solve 1 --submit 1");
-        process::exit(1);
+        return Ok(());
     }
 
     let part_index = args.iter().position(|x| x == "--submit").unwrap() + 1;
 
-    let Ok(part_submit) = args[part_index].parse::<u8>() else {
-        eprintln!("Unexpected command-line input. Format: cargo solve 1 --submit 1");
-        process::exit(1);
+    let Some(part_submit) = args.get(part_index).and_then(|x| x.parse::<u8>().ok()) else {
+        eprintln!("Error: unexpected command-line input. Format: cargo solve 1 --submit 1");
+        process::exit(EXIT_USAGE);
     };
 
     if part_submit != part {
-        return None;
+        return Ok(());
     }
 
-    if aoc_cli::check().is_err() {
-        eprintln!("command \"aoc\" not found or not callable. Try running \"cargo install aoc-cli\" to install it.");
-        process::exit(1);
-    }
+    aoc_cli::check()?;
 
     println!("Submitting result via aoc-cli...");
-    Some(aoc_cli::submit(day, part, &result.to_string()))
+    aoc_cli::submit(day, part, &result.to_string())?;
+    Ok(())
 }
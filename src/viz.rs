@@ -0,0 +1,933 @@
+/// Module that visualises solutions: styled grids and tables that are recorded as frames while a
+/// solution runs with `--visualize`, then played back in the terminal or exported as images.
+use std::collections::HashMap;
+use std::env;
+use std::ffi::OsStr;
+use std::fmt::Display;
+use std::fs;
+use std::io::{self, stdout, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, PoisonError};
+use std::thread;
+use std::time::Duration;
+
+use crate::grid::{Grid, Pos};
+use crate::style::{self, coloured, Colour};
+
+/// Playback speed in the terminal and in exported GIFs.
+pub const FRAMES_PER_SECOND: u16 = 10;
+
+/// The side length of the square of pixels that one cell is drawn as in exported images.
+pub const CELL_SIZE: usize = 4;
+
+const CLEAR_SCREEN: &str = "\x1b[2J\x1b[H";
+
+/* -------------------------------------------------------------------------- */
+
+/// What to do with the frames a solution recorded.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Visualize {
+    /// Play them back in the terminal, `--visualize`.
+    Play,
+    /// Write them to an image file, `--frames <path>`.
+    Export(String),
+}
+
+impl Visualize {
+    /// Returns what the solution was asked to do with its frames, if anything.
+    #[must_use]
+    pub fn from_args() -> Option<Self> {
+        let args: Vec<String> = env::args().collect();
+
+        if let Some(index) = args.iter().position(|x| x == "--frames") {
+            return args.get(index + 1).cloned().map(Visualize::Export);
+        }
+
+        args.iter()
+            .any(|x| x == "--visualize")
+            .then_some(Visualize::Play)
+    }
+
+    /// The arguments that request this from a solution binary.
+    #[must_use]
+    pub fn to_args(&self) -> Vec<String> {
+        match self {
+            Visualize::Play => vec!["--visualize".into()],
+            Visualize::Export(path) => vec!["--frames".into(), path.clone()],
+        }
+    }
+}
+
+static RECORDING: AtomicBool = AtomicBool::new(false);
+static FRAMES: Mutex<Vec<Frame>> = Mutex::new(Vec::new());
+
+/// Returns whether frames are being recorded. Solutions check this before building a visualisation,
+/// so that normal runs and benchmarks do not pay for it.
+#[must_use]
+pub fn enabled() -> bool {
+    RECORDING.load(Ordering::Relaxed)
+}
+
+/// Starts or stops recording. The runner only records the first run of every part.
+pub fn set_recording(recording: bool) {
+    RECORDING.store(recording, Ordering::Relaxed);
+}
+
+/// Records a frame, if recording.
+pub fn record(frame: Frame) {
+    if enabled() {
+        FRAMES
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push(frame);
+    }
+}
+
+/// Removes and returns all frames recorded so far.
+#[must_use]
+pub fn take_frames() -> Vec<Frame> {
+    std::mem::take(&mut *FRAMES.lock().unwrap_or_else(PoisonError::into_inner))
+}
+
+/// Plays back or exports the frames recorded for `part`, as requested on the command line.
+/// Exported files get the part appended to their name, e.g. `day3.gif` becomes `day3-1.gif`.
+pub fn finish(part: u8) -> io::Result<()> {
+    let frames = take_frames();
+    if frames.is_empty() {
+        return Ok(());
+    }
+
+    match Visualize::from_args() {
+        Some(Visualize::Export(path)) => {
+            let path = with_suffix(Path::new(&path), &part.to_string());
+            export(&frames, &path)?;
+            println!("Wrote {} frame(s) to {}", frames.len(), path.display());
+        }
+        Some(Visualize::Play) | None => play(&frames, FRAMES_PER_SECOND),
+    }
+
+    Ok(())
+}
+
+/// Prints the frames to the terminal, redrawing the screen for each one if there are several.
+pub fn play(frames: &[Frame], fps: u16) {
+    let mut stdout = stdout().lock();
+    let delay = Duration::from_secs(1) / u32::from(fps.max(1));
+
+    for (i, frame) in frames.iter().enumerate() {
+        // Redrawing needs a terminal, piped output gets the frames one after another.
+        if frames.len() > 1 && style::enabled() {
+            let _ = write!(stdout, "{CLEAR_SCREEN}");
+        }
+        let _ = writeln!(stdout, "{frame}");
+        let _ = stdout.flush();
+
+        if i + 1 < frames.len() {
+            thread::sleep(delay);
+        }
+    }
+}
+
+/// Appends `-suffix` to the file name of `path`, in front of its extension.
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let stem = path.file_stem().and_then(OsStr::to_str).unwrap_or_default();
+    let name = match path.extension().and_then(OsStr::to_str) {
+        Some(extension) => format!("{stem}-{suffix}.{extension}"),
+        None => format!("{stem}-{suffix}"),
+    };
+    path.with_file_name(name)
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// A character with an optional colour, the cell of a [`Frame`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Styled {
+    pub ch: char,
+    pub colour: Option<Colour>,
+}
+
+impl Display for Styled {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.ch)
+    }
+}
+
+/// A run of text with an optional colour, used to build a [`Frame`] line by line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Span {
+    pub text: String,
+    pub colour: Option<Colour>,
+}
+
+impl Span {
+    pub fn plain(text: impl Into<String>) -> Self {
+        Span {
+            text: text.into(),
+            colour: None,
+        }
+    }
+
+    pub fn coloured(text: impl Into<String>, colour: Colour) -> Self {
+        Span {
+            text: text.into(),
+            colour: Some(colour),
+        }
+    }
+}
+
+/// One picture of a visualisation: a rectangle of styled characters.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Frame {
+    cells: Grid<Styled>,
+}
+
+impl Frame {
+    /// Draws a grid whose cells display as single characters, colouring them like [`Grid::display_with`].
+    pub fn from_grid<T: Display>(
+        grid: &Grid<T>,
+        colour: impl Fn(Pos, &T) -> Option<Colour>,
+    ) -> Self {
+        let cells = grid.map(|pos, cell| Styled {
+            ch: cell.to_string().chars().next().unwrap_or(' '),
+            colour: colour(pos, cell),
+        });
+        Frame { cells }
+    }
+
+    /// Lays out lines of spans. Shorter lines are padded with spaces.
+    ///
+    /// ```
+    /// # use advent_of_code::viz::{Frame, Span};
+    /// # use advent_of_code::grid::Colour;
+    /// let frame = Frame::from_lines([
+    ///     vec![Span::plain("Card 1: "), Span::coloured("41", Colour::Red)],
+    ///     vec![Span::plain("Card 2")],
+    /// ]);
+    /// assert_eq!((frame.width(), frame.height()), (10, 2));
+    /// ```
+    pub fn from_lines(lines: impl IntoIterator<Item = Vec<Span>>) -> Self {
+        let lines: Vec<Vec<Styled>> = lines
+            .into_iter()
+            .map(|spans| {
+                spans
+                    .iter()
+                    .flat_map(|span| {
+                        span.text.chars().map(|ch| Styled {
+                            ch,
+                            colour: span.colour,
+                        })
+                    })
+                    .collect()
+            })
+            .collect();
+
+        let width = lines.iter().map(Vec::len).max().unwrap_or(0);
+        let blank = Styled {
+            ch: ' ',
+            colour: None,
+        };
+        let cells = lines
+            .iter()
+            .flat_map(|line| {
+                line.iter()
+                    .copied()
+                    .chain(std::iter::repeat(blank))
+                    .take(width)
+            })
+            .collect();
+
+        let cells = Grid::from_vec(width, lines.len(), cells).expect("lines are padded to width");
+        Frame { cells }
+    }
+
+    /// Lays out plain text.
+    #[must_use]
+    pub fn from_text(text: &str) -> Self {
+        Frame::from_lines(text.lines().map(|line| vec![Span::plain(line)]))
+    }
+
+    #[must_use]
+    pub fn width(&self) -> usize {
+        self.cells.width()
+    }
+
+    #[must_use]
+    pub fn height(&self) -> usize {
+        self.cells.height()
+    }
+
+    #[must_use]
+    pub fn cells(&self) -> &Grid<Styled> {
+        &self.cells
+    }
+
+    /// Colours the cell at `pos`, if it is within the frame.
+    pub fn highlight(&mut self, pos: Pos, colour: Colour) -> &mut Self {
+        if let Some(cell) = self.cells.get_mut(pos) {
+            cell.colour = Some(colour);
+        }
+        self
+    }
+
+    /// Rasterises the frame with one `scale`×`scale` square per cell. Whitespace and `.` cells are
+    /// drawn in the background colour, other uncoloured cells in the text colour.
+    #[must_use]
+    pub fn to_image(&self, scale: usize) -> Image {
+        let width = self.width() * scale;
+        let height = self.height() * scale;
+        let mut pixels = vec![0; width * height];
+
+        for ((x, y), cell) in self.cells.iter() {
+            let index = palette_index(cell);
+            for row in y * scale..(y + 1) * scale {
+                pixels[row * width + x * scale..row * width + (x + 1) * scale].fill(index);
+            }
+        }
+
+        Image {
+            width,
+            height,
+            pixels,
+        }
+    }
+}
+
+impl Display for Frame {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (y, row) in self.cells.rows().enumerate() {
+            if y > 0 {
+                writeln!(f)?;
+            }
+            // One escape sequence per run of equally coloured cells.
+            for run in row.chunk_by(|a, b| a.colour == b.colour) {
+                let text: String = run.iter().map(|cell| cell.ch).collect();
+                match run[0].colour {
+                    Some(colour) => write!(f, "{}", coloured(text, colour))?,
+                    None => write!(f, "{text}")?,
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// A table with aligned columns and optionally coloured rows.
+///
+/// ```
+/// # use advent_of_code::viz::Table;
+/// # use advent_of_code::grid::Colour;
+/// let mut table = Table::new(["Hand", "Bid"]);
+/// table.add_row(["32T3K", "765"], None);
+/// table.add_row(["T55J5", "684"], Some(Colour::Red));
+/// assert_eq!(table.to_string().lines().nth(2), Some("32T3K  765"));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Table {
+    headers: Vec<String>,
+    rows: Vec<(Vec<String>, Option<Colour>)>,
+}
+
+impl Table {
+    pub fn new<S: Display>(headers: impl IntoIterator<Item = S>) -> Self {
+        Table {
+            headers: headers.into_iter().map(|h| h.to_string()).collect(),
+            rows: vec![],
+        }
+    }
+
+    pub fn add_row<S: Display>(
+        &mut self,
+        cells: impl IntoIterator<Item = S>,
+        colour: Option<Colour>,
+    ) -> &mut Self {
+        let cells = cells.into_iter().map(|c| c.to_string()).collect();
+        self.rows.push((cells, colour));
+        self
+    }
+
+    /// Lays out the table as lines: a header, a rule, then one line per row. Columns are separated
+    /// by two spaces, numbers are aligned to the right.
+    fn lines(&self) -> Vec<(String, Option<Colour>)> {
+        let columns = self
+            .rows
+            .iter()
+            .map(|(cells, _)| cells.len())
+            .chain([self.headers.len()])
+            .max()
+            .unwrap_or(0);
+
+        let widths: Vec<usize> = (0..columns)
+            .map(|i| {
+                self.rows
+                    .iter()
+                    .map(|(cells, _)| cells)
+                    .chain([&self.headers])
+                    .filter_map(|cells| cells.get(i))
+                    .map(|cell| cell.chars().count())
+                    .max()
+                    .unwrap_or(0)
+            })
+            .collect();
+
+        let layout = |cells: &[String]| {
+            let line: Vec<String> = widths
+                .iter()
+                .enumerate()
+                .map(|(i, &width)| {
+                    let cell = cells.get(i).map_or("", String::as_str);
+                    if !cell.is_empty() && cell.bytes().all(|b| b.is_ascii_digit()) {
+                        format!("{cell:>width$}")
+                    } else {
+                        format!("{cell:<width$}")
+                    }
+                })
+                .collect();
+            line.join("  ").trim_end().to_string()
+        };
+
+        let rule = widths
+            .iter()
+            .map(|&width| "─".repeat(width))
+            .collect::<Vec<_>>()
+            .join("  ");
+
+        [(layout(&self.headers), None), (rule, None)]
+            .into_iter()
+            .chain(
+                self.rows
+                    .iter()
+                    .map(|(cells, colour)| (layout(cells), *colour)),
+            )
+            .collect()
+    }
+
+    #[must_use]
+    pub fn to_frame(&self) -> Frame {
+        Frame::from_lines(
+            self.lines()
+                .into_iter()
+                .map(|(text, colour)| vec![Span { text, colour }]),
+        )
+    }
+}
+
+impl Display for Table {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (i, (line, colour)) in self.lines().iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            match colour {
+                Some(colour) => write!(f, "{}", coloured(line, *colour))?,
+                None => write!(f, "{line}")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// The colours of exported images: background, text, then one per [`Colour`].
+pub const PALETTE: [[u8; 3]; 8] = [
+    [0x0f, 0x0f, 0x23],
+    [0xcc, 0xcc, 0xcc],
+    [0xe0, 0x4b, 0x4b],
+    [0x4b, 0xc0, 0x4b],
+    [0xff, 0xff, 0x66],
+    [0x4b, 0x7b, 0xe0],
+    [0xc0, 0x4b, 0xc0],
+    [0x4b, 0xc0, 0xc0],
+];
+
+fn palette_index(cell: &Styled) -> u8 {
+    match cell.colour {
+        Some(Colour::Red) => 2,
+        Some(Colour::Green) => 3,
+        Some(Colour::Yellow) => 4,
+        Some(Colour::Blue) => 5,
+        Some(Colour::Magenta) => 6,
+        Some(Colour::Cyan) => 7,
+        None if cell.ch.is_whitespace() || cell.ch == '.' => 0,
+        None => 1,
+    }
+}
+
+/// A picture in [`PALETTE`] colours, see [`Frame::to_image`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Image {
+    pub width: usize,
+    pub height: usize,
+    /// Row-major indices into [`PALETTE`].
+    pub pixels: Vec<u8>,
+}
+
+impl Image {
+    fn rgb(&self) -> impl Iterator<Item = u8> + '_ {
+        self.pixels
+            .iter()
+            .flat_map(|&index| PALETTE[usize::from(index)])
+    }
+
+    /// Encodes the image as a binary PPM (`P6`) file.
+    #[must_use]
+    pub fn to_ppm(&self) -> Vec<u8> {
+        let mut bytes = format!("P6\n{} {}\n255\n", self.width, self.height).into_bytes();
+        bytes.extend(self.rgb());
+        bytes
+    }
+
+    /// Encodes the image as an RGB PNG file. The pixel data is stored without compression.
+    #[must_use]
+    pub fn to_png(&self) -> Vec<u8> {
+        let mut header = vec![];
+        header.extend(u32_be(self.width));
+        header.extend(u32_be(self.height));
+        // 8 bits per channel, RGB, default compression, filtering and no interlacing.
+        header.extend([8, 2, 0, 0, 0]);
+
+        // Every scanline starts with filter type 0 (none).
+        let mut scanlines = Vec::with_capacity((self.width * 3 + 1) * self.height);
+        let rgb: Vec<u8> = self.rgb().collect();
+        for row in rgb.chunks(self.width * 3).take(self.height) {
+            scanlines.push(0);
+            scanlines.extend(row);
+        }
+
+        let mut bytes = b"\x89PNG\r\n\x1a\n".to_vec();
+        png_chunk(&mut bytes, b"IHDR", &header);
+        png_chunk(&mut bytes, b"IDAT", &zlib_stored(&scanlines));
+        png_chunk(&mut bytes, b"IEND", &[]);
+        bytes
+    }
+}
+
+fn u32_be(n: usize) -> [u8; 4] {
+    u32::try_from(n)
+        .expect("image dimensions fit into 32 bits")
+        .to_be_bytes()
+}
+
+fn png_chunk(bytes: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    bytes.extend(u32_be(data.len()));
+    let start = bytes.len();
+    bytes.extend(kind);
+    bytes.extend(data);
+    let crc = crc32(&bytes[start..]);
+    bytes.extend(crc.to_be_bytes());
+}
+
+/// Wraps `data` into a zlib stream of uncompressed deflate blocks.
+fn zlib_stored(data: &[u8]) -> Vec<u8> {
+    // Deflate with a 32K window, no preset dictionary, checksummed header.
+    let mut bytes = vec![0x78, 0x01];
+    let mut blocks = data.chunks(usize::from(u16::MAX)).peekable();
+
+    if blocks.peek().is_none() {
+        bytes.extend([1, 0, 0, 0xff, 0xff]);
+    }
+    while let Some(block) = blocks.next() {
+        let is_last = blocks.peek().is_none();
+        let len = u16::try_from(block.len()).expect("blocks are at most u16::MAX long");
+        bytes.push(u8::from(is_last));
+        bytes.extend(len.to_le_bytes());
+        bytes.extend((!len).to_le_bytes());
+        bytes.extend(block);
+    }
+
+    bytes.extend(adler32(data).to_be_bytes());
+    bytes
+}
+
+const CRC_TABLE: [u32; 256] = crc_table();
+
+const fn crc_table() -> [u32; 256] {
+    let mut table = [0; 256];
+    let mut n = 0;
+    while n < 256 {
+        let mut c = n as u32;
+        let mut k = 0;
+        while k < 8 {
+            c = if c & 1 == 1 {
+                0xedb8_8320 ^ (c >> 1)
+            } else {
+                c >> 1
+            };
+            k += 1;
+        }
+        table[n] = c;
+        n += 1;
+    }
+    table
+}
+
+fn crc32(data: &[u8]) -> u32 {
+    !data.iter().fold(!0, |crc, &byte| {
+        CRC_TABLE[usize::from((crc as u8) ^ byte)] ^ (crc >> 8)
+    })
+}
+
+fn adler32(data: &[u8]) -> u32 {
+    const MOD: u32 = 65521;
+    let (a, b) = data.iter().fold((1, 0), |(a, b), &byte| {
+        let a = (a + u32::from(byte)) % MOD;
+        (a, (b + a) % MOD)
+    });
+    (b << 16) | a
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// The number of bits of a [`PALETTE`] index, as the LZW minimum code size of GIF image data.
+const GIF_CODE_SIZE: u8 = 3;
+
+/// Encodes the images as an endlessly looping GIF animation, showing each for `delay` hundredths of a second.
+#[must_use]
+pub fn encode_gif(images: &[Image], delay: u16) -> Vec<u8> {
+    let width = images.iter().map(|image| image.width).max().unwrap_or(0);
+    let height = images.iter().map(|image| image.height).max().unwrap_or(0);
+
+    let mut bytes = b"GIF89a".to_vec();
+    bytes.extend(u16_le(width));
+    bytes.extend(u16_le(height));
+    // Global colour table of 2^(2 + 1) entries with 8 bit colours, background index 0, square pixels.
+    bytes.extend([0b1111_0010, 0, 0]);
+    bytes.extend(PALETTE.iter().flatten());
+
+    // Loop forever.
+    bytes.extend([0x21, 0xff, 0x0b]);
+    bytes.extend(b"NETSCAPE2.0");
+    bytes.extend([0x03, 0x01, 0x00, 0x00, 0x00]);
+
+    for image in images {
+        // Graphic control: restore to background before the next frame, so smaller frames do not leave traces.
+        bytes.extend([0x21, 0xf9, 0x04, 0b0000_1000]);
+        bytes.extend(delay.to_le_bytes());
+        bytes.extend([0x00, 0x00]);
+
+        // Image descriptor at the top left, without a local colour table.
+        bytes.extend([0x2c, 0, 0, 0, 0]);
+        bytes.extend(u16_le(image.width));
+        bytes.extend(u16_le(image.height));
+        bytes.push(0);
+
+        bytes.push(GIF_CODE_SIZE);
+        for block in lzw_encode(&image.pixels, GIF_CODE_SIZE).chunks(255) {
+            bytes.push(u8::try_from(block.len()).expect("sub-blocks are at most 255 bytes long"));
+            bytes.extend(block);
+        }
+        bytes.push(0);
+    }
+
+    bytes.push(0x3b);
+    bytes
+}
+
+fn u16_le(n: usize) -> [u8; 2] {
+    u16::try_from(n)
+        .expect("GIF dimensions fit into 16 bits")
+        .to_le_bytes()
+}
+
+/// Packs variable-width codes into bytes, least significant bit first.
+#[derive(Default)]
+struct BitWriter {
+    bytes: Vec<u8>,
+    buffer: u32,
+    bits: u8,
+}
+
+impl BitWriter {
+    fn write(&mut self, code: u16, size: u8) {
+        self.buffer |= u32::from(code) << self.bits;
+        self.bits += size;
+        while self.bits >= 8 {
+            self.bytes.push(self.buffer as u8);
+            self.buffer >>= 8;
+            self.bits -= 8;
+        }
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        if self.bits > 0 {
+            self.bytes.push(self.buffer as u8);
+        }
+        self.bytes
+    }
+}
+
+/// Compresses palette indices with the variable-width LZW flavour of GIF, resetting the
+/// dictionary whenever it is full.
+fn lzw_encode(indices: &[u8], min_code_size: u8) -> Vec<u8> {
+    const MAX_CODES: u16 = 1 << 12;
+    let clear = 1u16 << min_code_size;
+    let end = clear + 1;
+
+    let mut writer = BitWriter::default();
+    let mut dictionary: HashMap<(u16, u8), u16> = HashMap::new();
+    let mut next_code = end + 1;
+    let mut size = min_code_size + 1;
+
+    // The decoder widens its codes once the dictionary outgrows them, i.e. right after the
+    // code that precedes the entry `1 << size` was written.
+    fn emit(writer: &mut BitWriter, code: u16, next_code: u16, size: &mut u8) {
+        writer.write(code, *size);
+        if next_code > (1 << *size) - 1 && *size < 12 {
+            *size += 1;
+        }
+    }
+
+    writer.write(clear, size);
+    let mut prefix: Option<u16> = None;
+
+    for &index in indices {
+        let Some(code) = prefix else {
+            prefix = Some(u16::from(index));
+            continue;
+        };
+
+        if let Some(&extended) = dictionary.get(&(code, index)) {
+            prefix = Some(extended);
+            continue;
+        }
+
+        emit(&mut writer, code, next_code, &mut size);
+        if next_code < MAX_CODES {
+            dictionary.insert((code, index), next_code);
+            next_code += 1;
+        } else {
+            writer.write(clear, size);
+            dictionary.clear();
+            next_code = end + 1;
+            size = min_code_size + 1;
+        }
+        prefix = Some(u16::from(index));
+    }
+
+    if let Some(code) = prefix {
+        emit(&mut writer, code, next_code, &mut size);
+    }
+    writer.write(end, size);
+    writer.finish()
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// Writes the frames to `path`, in the format of its extension: `.gif` is an animation of all
+/// frames, `.png` and `.ppm` write one file per frame, numbered if there are several.
+pub fn export(frames: &[Frame], path: &Path) -> io::Result<()> {
+    let images: Vec<Image> = frames.iter().map(|f| f.to_image(CELL_SIZE)).collect();
+
+    let encode: fn(&Image) -> Vec<u8> = match path.extension().and_then(OsStr::to_str) {
+        Some("gif") => return fs::write(path, encode_gif(&images, 100 / FRAMES_PER_SECOND)),
+        Some("png") => Image::to_png,
+        Some("ppm") => Image::to_ppm,
+        _ => {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "can not export frames to {}, expecting a .gif, .png or .ppm file",
+                    path.display()
+                ),
+            ))
+        }
+    };
+
+    match &images[..] {
+        [image] => fs::write(path, encode(image)),
+        images => images.iter().enumerate().try_for_each(|(i, image)| {
+            fs::write(with_suffix(path, &format!("{:04}", i + 1)), encode(image))
+        }),
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{
+        adler32, crc32, encode_gif, lzw_encode, with_suffix, zlib_stored, Frame, Image, Span,
+        Table, GIF_CODE_SIZE,
+    };
+    use crate::grid::{Colour, Grid};
+    use crate::style::{set_color_choice, ColorChoice};
+    use std::path::{Path, PathBuf};
+
+    #[test]
+    fn builds_frames() {
+        set_color_choice(ColorChoice::Always);
+        let grid = Grid::parse("#.\n.#", |c| c).unwrap();
+        let mut frame = Frame::from_grid(&grid, |_, &c| (c == '#').then_some(Colour::Red));
+        frame
+            .highlight((1, 0), Colour::Blue)
+            .highlight((5, 5), Colour::Blue);
+        assert_eq!(
+            frame.to_string(),
+            "\x1b[31m#\x1b[0m\x1b[34m.\x1b[0m\n.\x1b[31m#\x1b[0m"
+        );
+
+        let frame =
+            Frame::from_lines([vec![Span::plain("ab"), Span::coloured("c", Colour::Green)]]);
+        assert_eq!(frame.to_string(), "ab\x1b[32mc\x1b[0m");
+        assert_eq!(Frame::from_text("a\nbcd").to_string(), "a  \nbcd");
+    }
+
+    #[test]
+    fn lays_out_tables() {
+        set_color_choice(ColorChoice::Always);
+        let mut table = Table::new(["Rank", "Hand", "Bid"]);
+        table
+            .add_row(["1", "32T3K", "765"], None)
+            .add_row(["10", "KK677", "28"], Some(Colour::Red));
+        assert_eq!(
+            table.to_string(),
+            "Rank  Hand   Bid\n────  ─────  ───\n   1  32T3K  765\n\x1b[31m  10  KK677   28\x1b[0m"
+        );
+    }
+
+    #[test]
+    fn rasterises_frames() {
+        let frame = Frame::from_lines([vec![Span::plain("#."), Span::coloured("x", Colour::Red)]]);
+        let image = frame.to_image(2);
+        assert_eq!((image.width, image.height), (6, 2));
+        assert_eq!(image.pixels, [1, 1, 0, 0, 2, 2, 1, 1, 0, 0, 2, 2]);
+
+        let ppm = image.to_ppm();
+        assert!(ppm.starts_with(b"P6\n6 2\n255\n"));
+        assert_eq!(ppm.len(), 11 + 6 * 2 * 3);
+    }
+
+    #[test]
+    fn computes_checksums() {
+        assert_eq!(crc32(b"123456789"), 0xcbf4_3926);
+        assert_eq!(crc32(b"IEND"), 0xae42_6082);
+        assert_eq!(adler32(b"Wikipedia"), 0x11e6_0398);
+    }
+
+    #[test]
+    fn encodes_png() {
+        let data = vec![7; 70_000];
+        let zlib = zlib_stored(&data);
+        // Header, two stored blocks with their 5 byte headers, checksum.
+        assert_eq!(zlib.len(), 2 + 5 + 65_535 + 5 + 4465 + 4);
+        assert_eq!(zlib[2], 0);
+        assert_eq!(zlib[2 + 5 + 65_535], 1);
+
+        let image = Image {
+            width: 1,
+            height: 1,
+            pixels: vec![2],
+        };
+        let png = image.to_png();
+        assert!(png.starts_with(b"\x89PNG\r\n\x1a\n\0\0\0\x0dIHDR\0\0\0\x01\0\0\0\x01\x08\x02"));
+        assert!(png.ends_with(b"\0\0\0\0IEND\xae\x42\x60\x82"));
+    }
+
+    /// A straightforward GIF LZW decoder to check the encoder against.
+    fn lzw_decode(data: &[u8], min_code_size: u8) -> Vec<u8> {
+        let clear = 1usize << min_code_size;
+        let reset = || -> Vec<Vec<u8>> {
+            (0..clear + 2)
+                .map(|i| vec![u8::try_from(i).unwrap_or(0)])
+                .collect()
+        };
+
+        let mut table = reset();
+        let mut size = min_code_size + 1;
+        let mut previous: Option<Vec<u8>> = None;
+        let mut output = vec![];
+        let (mut buffer, mut bits, mut bytes) = (0u32, 0u8, data.iter());
+
+        loop {
+            while bits < size {
+                let Some(&byte) = bytes.next() else {
+                    return output;
+                };
+                buffer |= u32::from(byte) << bits;
+                bits += 8;
+            }
+            let code = (buffer & ((1 << size) - 1)) as usize;
+            buffer >>= size;
+            bits -= size;
+
+            if code == clear {
+                table = reset();
+                size = min_code_size + 1;
+                previous = None;
+                continue;
+            }
+            if code == clear + 1 {
+                return output;
+            }
+
+            let entry = match (table.get(code), &previous) {
+                (Some(entry), _) => entry.clone(),
+                (None, Some(previous)) => [previous.clone(), vec![previous[0]]].concat(),
+                (None, None) => panic!("invalid first code {code}"),
+            };
+            output.extend(&entry);
+
+            if let Some(mut previous) = previous {
+                previous.push(entry[0]);
+                if table.len() < 4096 {
+                    table.push(previous);
+                }
+                if table.len() == 1 << size && size < 12 {
+                    size += 1;
+                }
+            }
+            previous = Some(entry);
+        }
+    }
+
+    #[test]
+    fn lzw_round_trips() {
+        // Enough noise to fill the dictionary and force clear codes.
+        let mut state = 1u32;
+        let noise: Vec<u8> = (0..50_000)
+            .map(|_| {
+                state = state.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+                ((state >> 16) % 8) as u8
+            })
+            .collect();
+
+        for indices in [vec![], vec![3], vec![0; 1000], noise] {
+            let encoded = lzw_encode(&indices, GIF_CODE_SIZE);
+            assert_eq!(lzw_decode(&encoded, GIF_CODE_SIZE), indices);
+        }
+    }
+
+    #[test]
+    fn encodes_gif() {
+        let frames = [
+            Frame::from_text("#.\n.#").to_image(1),
+            Frame::from_text("###").to_image(1),
+        ];
+        let gif = encode_gif(&frames, 10);
+        assert!(gif.starts_with(b"GIF89a\x03\x00\x02\x00"));
+        assert_eq!(gif.last(), Some(&0x3b));
+        assert_eq!(gif.windows(11).filter(|w| w == b"NETSCAPE2.0").count(), 1);
+        assert_eq!(
+            gif.windows(4)
+                .filter(|w| w == &[0x21, 0xf9, 0x04, 0x08])
+                .count(),
+            2
+        );
+    }
+
+    #[test]
+    fn names_exported_files() {
+        assert_eq!(
+            with_suffix(Path::new("out/day3.gif"), "1"),
+            PathBuf::from("out/day3-1.gif")
+        );
+        assert_eq!(
+            with_suffix(Path::new("frames"), "0002"),
+            PathBuf::from("frames-0002")
+        );
+    }
+}